@@ -0,0 +1,134 @@
+// Datos atómicos (capa electrónica, no el núcleo: ver `nuclear.rs`)
+
+// Primera energía de ionización (eV) de los elementos Z = 1..=103 (NIST)
+const FIRST_IONIZATION_ENERGIES: [f64; 103] = [
+    13.598, 24.587, // H, He
+    5.392, 9.323, 8.298, 11.260, 14.534, 13.618, 17.423, 21.565, // Li..Ne
+    5.139, 7.646, 5.986, 8.152, 10.487, 10.360, 12.968, 15.760, // Na..Ar
+    4.341, 6.113, 6.561, 6.828, 6.746, 6.767, 7.434, 7.902, 7.881, 7.640, 7.726, 9.394, // K..Zn
+    5.999, 7.900, 9.815, 9.752, 11.814, 14.000, // Ga..Kr
+    4.177, 5.695, 6.217, 6.634, 6.759, 7.092, 7.280, 7.361, 7.459, 8.337, 7.576, 8.994, // Rb..Cd
+    5.786, 7.344, 8.608, 9.010, 10.451, 12.130, // In..Xe
+    3.894, 5.212, // Cs, Ba
+    5.577, 5.539, 5.473, 5.525, 5.582, 5.644, 5.670, 6.150, 5.864, 5.939, 6.022, 6.108, 6.184, 6.254, 5.426, // La..Lu
+    6.825, 7.550, 7.864, 7.834, 8.438, 8.967, 8.959, 9.226, 10.438, // Hf..Hg
+    6.108, 7.417, 7.286, 8.414, 9.318, 10.749, // Tl..Rn
+    4.073, 5.278, // Fr, Ra
+    5.380, 6.307, 5.890, 6.194, 6.266, 6.026, 5.974, 5.991, 6.198, 6.282, 6.368, 6.500, 6.580, 6.626, 4.960, // Ac..Lr
+];
+
+// Estimación para los elementos superpesados sin medida
+const UNKNOWN_IONIZATION_ENERGY: f64 = 6.0;
+
+// Energía para arrancar el electrón sobrante de un anión (afinidad electrónica típica)
+const ELECTRON_DETACHMENT_ENERGY: f64 = 1.0;
+
+pub fn first_ionization_energy(protons: usize) -> f64 {
+    match protons {
+        0 => 0.0,
+        z => FIRST_IONIZATION_ENERGIES.get(z - 1).copied().unwrap_or(UNKNOWN_IONIZATION_ENERGY),
+    }
+}
+
+// Energía (eV) para arrancar el electrón más externo de un átomo con `protons`
+// protones y `electrons` electrones ligados. Para iones positivos se usa la serie
+// isoelectrónica: el neutro con Z = `electrons` escalado por la carga que ve el
+// electrón, (Z − electrones + 1)², exacto para los hidrogenoides (13.6·Z² eV).
+pub fn ionization_energy(protons: usize, electrons: usize) -> f64 {
+    if electrons == 0 {
+        return 0.0; // Nada que ionizar
+    }
+    if electrons > protons {
+        return ELECTRON_DETACHMENT_ENERGY;
+    }
+    let screened_charge = (protons - electrons + 1) as f64;
+    first_ionization_energy(electrons) * screened_charge * screened_charge
+}
+
+// Electronegatividad de Pauling de los elementos Z = 1..=103 (0 = sin valor: He, Ne, Ar)
+const ELECTRONEGATIVITIES: [f64; 103] = [
+    2.20, 0.0, // H, He
+    0.98, 1.57, 2.04, 2.55, 3.04, 3.44, 3.98, 0.0, // Li..Ne
+    0.93, 1.31, 1.61, 1.90, 2.19, 2.58, 3.16, 0.0, // Na..Ar
+    0.82, 1.00, 1.36, 1.54, 1.63, 1.66, 1.55, 1.83, 1.88, 1.91, 1.90, 1.65, // K..Zn
+    1.81, 2.01, 2.18, 2.55, 2.96, 3.00, // Ga..Kr
+    0.82, 0.95, 1.22, 1.33, 1.60, 2.16, 1.90, 2.20, 2.28, 2.20, 1.93, 1.69, // Rb..Cd
+    1.78, 1.96, 2.05, 2.10, 2.66, 2.60, // In..Xe
+    0.79, 0.89, // Cs, Ba
+    1.10, 1.12, 1.13, 1.14, 1.13, 1.17, 1.20, 1.20, 1.10, 1.22, 1.23, 1.24, 1.25, 1.10, 1.27, // La..Lu
+    1.30, 1.50, 2.36, 1.90, 2.20, 2.20, 2.28, 2.54, 2.00, // Hf..Hg
+    1.62, 2.33, 2.02, 2.00, 2.20, 2.20, // Tl..Rn
+    0.70, 0.90, // Fr, Ra
+    1.10, 1.30, 1.50, 1.38, 1.36, 1.28, 1.13, 1.28, 1.30, 1.30, 1.30, 1.30, 1.30, 1.30, 1.30, // Ac..Lr
+];
+
+pub fn electronegativity(protons: usize) -> Option<f64> {
+    match protons {
+        0 => None,
+        z => ELECTRONEGATIVITIES.get(z - 1).copied().filter(|&chi| chi > 0.0),
+    }
+}
+
+// Constante de Rydberg (eV) y h·c (eV·nm)
+pub const RYDBERG_ENERGY: f64 = 13.606;
+const PLANCK_TIMES_LIGHT_SPEED: f64 = 1239.84;
+
+// Longitud de onda (nm) de un fotón de `energy` eV
+pub fn photon_wavelength(energy: f64) -> f64 {
+    PLANCK_TIMES_LIGHT_SPEED / energy
+}
+
+// Subniveles en el orden de llenado de `Simulation::get_orbital_info`: (n, subnivel)
+pub(crate) const SUBSHELLS: [(usize, char); 13] = [
+    (1, 's'),
+    (2, 's'),
+    (2, 'p'),
+    (3, 's'),
+    (3, 'p'),
+    (4, 's'),
+    (3, 'd'),
+    (4, 'p'),
+    (5, 's'),
+    (4, 'd'),
+    (5, 'p'),
+    (6, 's'),
+    (4, 'f'),
+];
+
+// Número cuántico l de un subnivel
+pub fn angular_momentum(subshell: char) -> usize {
+    match subshell {
+        's' => 0,
+        'p' => 1,
+        'd' => 2,
+        'f' => 3,
+        _ => 0,
+    }
+}
+
+// Defecto cuántico aproximado (apantallamiento de los electrones internos, mayor
+// cuanto más penetra el orbital); típico de los átomos alcalinos ligeros
+fn quantum_defect(subshell: char) -> f64 {
+    match subshell {
+        's' => 0.5,
+        'p' => 0.3,
+        'd' => 0.05,
+        _ => 0.0,
+    }
+}
+
+// Energía (eV, negativa) de un electrón en el subnivel (n, subnivel) de un átomo con
+// `protons` protones y `electrons` electrones ligados: Rydberg con la carga que ve el
+// electrón más externo, −13.6·(Z − electrones + 1)²/n*², con n* = n − defecto cuántico.
+// Exacta para los hidrogenoides (un solo electrón, sin defecto).
+pub fn level_energy(protons: usize, electrons: usize, n: usize, subshell: char) -> f64 {
+    let charge = (protons as f64 - electrons as f64 + 1.0).max(1.0);
+    let defect = if electrons > 1 { quantum_defect(subshell) } else { 0.0 };
+    let effective_n = (n as f64 - defect).max(1.0);
+    -RYDBERG_ENERGY * charge * charge / (effective_n * effective_n)
+}
+
+// Transición dipolar eléctrica permitida: Δl = ±1
+pub fn transition_allowed(from: char, to: char) -> bool {
+    angular_momentum(from).abs_diff(angular_momentum(to)) == 1
+}
@@ -0,0 +1,220 @@
+use crate::particle::{Particle, ParticleType};
+use crate::physics::{Physics, PhysicsParams};
+
+// Profundidad máxima: evita recursión infinita con partículas superpuestas
+const MAX_DEPTH: usize = 24;
+
+struct Node {
+    // Cuadrado que cubre la celda
+    x0: f64,
+    y0: f64,
+    size: f64,
+    // Monopolo agregado: carga total y centro de carga
+    charge: f64,
+    center_x: f64,
+    center_y: f64,
+    protons: usize,
+    electrons: usize,
+    children: Vec<usize>,
+    bodies: Vec<usize>, // Solo en hojas
+}
+
+// Quadtree de Barnes-Hut sobre un subconjunto de partículas del mismo signo de carga
+pub struct QuadTree {
+    nodes: Vec<Node>,
+}
+
+impl QuadTree {
+    pub fn build(particles: &[Particle], indices: &[usize]) -> Self {
+        let mut tree = QuadTree { nodes: Vec::new() };
+        if indices.is_empty() {
+            return tree;
+        }
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &i in indices {
+            min_x = min_x.min(particles[i].x);
+            min_y = min_y.min(particles[i].y);
+            max_x = max_x.max(particles[i].x);
+            max_y = max_y.max(particles[i].y);
+        }
+        let size = (max_x - min_x).max(max_y - min_y).max(1.0);
+
+        tree.build_node(particles, indices.to_vec(), min_x, min_y, size, 0);
+        tree
+    }
+
+    fn build_node(&mut self, particles: &[Particle], indices: Vec<usize>, x0: f64, y0: f64, size: f64, depth: usize) -> usize {
+        let node_idx = self.nodes.len();
+        self.nodes.push(Node {
+            x0,
+            y0,
+            size,
+            charge: 0.0,
+            center_x: 0.0,
+            center_y: 0.0,
+            protons: 0,
+            electrons: 0,
+            children: Vec::new(),
+            bodies: Vec::new(),
+        });
+
+        // Agregados de la celda (centro ponderado por |q|)
+        let mut weight = 0.0;
+        let (mut center_x, mut center_y, mut charge) = (0.0, 0.0, 0.0);
+        let (mut protons, mut electrons) = (0, 0);
+        for &i in &indices {
+            let p = &particles[i];
+            let w = p.charge.abs();
+            weight += w;
+            center_x += p.x * w;
+            center_y += p.y * w;
+            charge += p.charge;
+            match p.ptype {
+                ParticleType::Proton => protons += 1,
+                ParticleType::Electron => electrons += 1,
+                _ => {}
+            }
+        }
+        {
+            let node = &mut self.nodes[node_idx];
+            node.charge = charge;
+            node.center_x = if weight > 0.0 { center_x / weight } else { x0 + size * 0.5 };
+            node.center_y = if weight > 0.0 { center_y / weight } else { y0 + size * 0.5 };
+            node.protons = protons;
+            node.electrons = electrons;
+        }
+
+        if indices.len() <= 1 || depth >= MAX_DEPTH {
+            self.nodes[node_idx].bodies = indices;
+            return node_idx;
+        }
+
+        // Repartir en los cuatro cuadrantes
+        let half = size * 0.5;
+        let mut quadrants: [Vec<usize>; 4] = Default::default();
+        for i in indices {
+            let right = particles[i].x >= x0 + half;
+            let bottom = particles[i].y >= y0 + half;
+            quadrants[right as usize + 2 * bottom as usize].push(i);
+        }
+
+        let mut children = Vec::new();
+        for (q, quadrant) in quadrants.into_iter().enumerate() {
+            if quadrant.is_empty() {
+                continue;
+            }
+            let cx = x0 + if q % 2 == 1 { half } else { 0.0 };
+            let cy = y0 + if q / 2 == 1 { half } else { 0.0 };
+            children.push(self.build_node(particles, quadrant, cx, cy, half, depth + 1));
+        }
+        self.nodes[node_idx].children = children;
+        node_idx
+    }
+
+    // Fuerza de largo alcance sobre la partícula `target` usando el criterio size/d < theta
+    pub fn force_on(&self, particles: &[Particle], target: usize, theta: f64, params: &PhysicsParams) -> (f64, f64) {
+        let mut force = (0.0, 0.0);
+        if self.nodes.is_empty() {
+            return force;
+        }
+
+        let particle = &particles[target];
+        let mut add_force = |to_x: f64, to_y: f64, charge: f64, protons: usize, electrons: usize| {
+            let dx = to_x - particle.x;
+            let dy = to_y - particle.y;
+            let distance = (dx * dx + dy * dy).sqrt().max(1.0);
+            let magnitude = Physics::calculate_long_range_force(distance, particle, charge, protons, electrons, params);
+            force.0 += magnitude * dx / distance;
+            force.1 += magnitude * dy / distance;
+        };
+
+        let mut stack = vec![0];
+        while let Some(node_idx) = stack.pop() {
+            let node = &self.nodes[node_idx];
+
+            if node.children.is_empty() {
+                for &j in &node.bodies {
+                    if j == target {
+                        continue;
+                    }
+                    let other = &particles[j];
+                    add_force(
+                        other.x,
+                        other.y,
+                        other.charge,
+                        (other.ptype == ParticleType::Proton) as usize,
+                        (other.ptype == ParticleType::Electron) as usize,
+                    );
+                }
+                continue;
+            }
+
+            let contains_target = particle.x >= node.x0
+                && particle.x <= node.x0 + node.size
+                && particle.y >= node.y0
+                && particle.y <= node.y0 + node.size;
+            let dx = node.center_x - particle.x;
+            let dy = node.center_y - particle.y;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            if !contains_target && distance > 0.0 && node.size / distance < theta {
+                add_force(node.center_x, node.center_y, node.charge, node.protons, node.electrons);
+            } else {
+                stack.extend_from_slice(&node.children);
+            }
+        }
+
+        force
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::particle::ParticleType;
+    use crate::rng::Rng;
+    use crate::simulation::Simulation;
+
+    // Protones y electrones dispersos y libres (ninguno orbita aún)
+    fn charged_scene() -> Simulation {
+        let mut simulation = Simulation::new(1000.0, 800.0);
+        let mut rng = Rng::new(11);
+        for k in 0..200 {
+            let ptype = if k % 2 == 0 { ParticleType::Proton } else { ParticleType::Electron };
+            simulation.add_particle(rng.next_f64() * 1000.0, rng.next_f64() * 800.0, ptype);
+        }
+        simulation
+    }
+
+    // Mayor error de la aproximación relativo a la fuerza exacta media
+    fn relative_error(theta: f64) -> f64 {
+        let mut simulation = charged_scene();
+        let exact = simulation.calculate_forces(&simulation.particles);
+        simulation.params.barnes_hut = true;
+        simulation.params.barnes_hut_theta = theta;
+        let approx = simulation.calculate_forces(&simulation.particles);
+
+        let scale = exact.iter().map(|f| f.0.hypot(f.1)).sum::<f64>() / exact.len() as f64;
+        exact
+            .iter()
+            .zip(&approx)
+            .map(|(e, a)| (e.0 - a.0).hypot(e.1 - a.1) / scale)
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn matches_brute_force_within_tolerance() {
+        assert!(relative_error(0.5) < 0.02);
+    }
+
+    #[test]
+    fn zero_theta_is_exact() {
+        assert!(relative_error(0.0) < 1e-9);
+    }
+
+    #[test]
+    fn error_grows_with_theta() {
+        assert!(relative_error(0.3) < relative_error(1.0));
+    }
+}
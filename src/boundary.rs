@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+// Qué les pasa a las partículas en los bordes del lienzo
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum BoundaryMode {
+    // Rebote elástico contra las paredes (comportamiento original)
+    #[default]
+    Reflective,
+    // Lo que sale por un borde entra por el opuesto; las fuerzas usan la imagen más cercana
+    Periodic,
+    // Las partículas libres que salen del lienzo se eliminan
+    Absorbing,
+    // Sin paredes: las partículas siguen fuera del lienzo
+    Open,
+}
+
+impl BoundaryMode {
+    pub fn from_index(index: u32) -> Self {
+        match index {
+            0 => BoundaryMode::Reflective,
+            1 => BoundaryMode::Periodic,
+            2 => BoundaryMode::Absorbing,
+            3 => BoundaryMode::Open,
+            _ => BoundaryMode::Reflective,
+        }
+    }
+
+    // Separación (dx, dy) entre dos partículas; con contorno periódico, la de la imagen más cercana
+    pub fn minimum_image(self, dx: f64, dy: f64, width: f64, height: f64) -> (f64, f64) {
+        match self {
+            BoundaryMode::Periodic => (dx - width * (dx / width).round(), dy - height * (dy / height).round()),
+            _ => (dx, dy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleType;
+    use crate::simulation::Simulation;
+
+    fn scene(boundary: BoundaryMode) -> Simulation {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.boundary = boundary;
+        simulation
+    }
+
+    #[test]
+    fn periodic_separation_uses_the_nearest_image() {
+        let (dx, dy) = BoundaryMode::Periodic.minimum_image(980.0, -590.0, 1000.0, 600.0);
+        assert!((dx + 20.0).abs() < 1e-9);
+        assert!((dy - 10.0).abs() < 1e-9);
+        assert_eq!(BoundaryMode::Reflective.minimum_image(980.0, -590.0, 1000.0, 600.0), (980.0, -590.0));
+    }
+
+    #[test]
+    fn periodic_particles_reenter_through_the_opposite_edge() {
+        let mut simulation = scene(BoundaryMode::Periodic);
+        let id = simulation.add_particle_with_velocity(995.0, 300.0, ParticleType::Neutron, 5.0, 0.0);
+        for _ in 0..4 {
+            simulation.update();
+        }
+        let neutron = simulation.get_particle(id).unwrap();
+        assert!(neutron.x > 0.0 && neutron.x < 100.0);
+        assert_eq!(neutron.dx, 5.0);
+    }
+
+    #[test]
+    fn reflective_walls_bounce_without_losing_speed() {
+        let mut simulation = scene(BoundaryMode::Reflective);
+        let id = simulation.add_particle_with_velocity(990.0, 300.0, ParticleType::Neutron, 5.0, -2.0);
+        for _ in 0..10 {
+            simulation.update();
+        }
+        let neutron = simulation.get_particle(id).unwrap();
+        assert!(neutron.x + neutron.radius <= 1000.0);
+        assert_eq!((neutron.dx, neutron.dy), (-5.0, -2.0));
+    }
+
+    #[test]
+    fn absorbing_walls_remove_free_particles() {
+        let mut simulation = scene(BoundaryMode::Absorbing);
+        let lost = simulation.add_particle_with_velocity(995.0, 300.0, ParticleType::Neutron, 5.0, 0.0);
+        let kept = simulation.add_particle(500.0, 300.0, ParticleType::Neutron);
+        for _ in 0..10 {
+            simulation.update();
+        }
+        assert!(simulation.get_particle(lost).is_none());
+        assert!(simulation.get_particle(kept).is_some());
+
+        // Sin paredes la partícula sigue fuera del lienzo
+        let mut open = scene(BoundaryMode::Open);
+        let id = open.add_particle_with_velocity(995.0, 300.0, ParticleType::Neutron, 5.0, 0.0);
+        for _ in 0..10 {
+            open.update();
+        }
+        assert!(open.get_particle(id).unwrap().x > 1000.0);
+    }
+
+    #[test]
+    fn periodic_nuclei_span_the_edge() {
+        let nuclei = |boundary| {
+            let mut simulation = scene(boundary);
+            simulation.add_particle(5.0, 300.0, ParticleType::Proton);
+            simulation.add_particle(975.0, 300.0, ParticleType::Neutron);
+            simulation.detect_nuclei().iter().map(|n| (n.protons.len(), n.neutrons.len())).collect::<Vec<_>>()
+        };
+        assert_eq!(nuclei(BoundaryMode::Periodic), [(1, 1)]);
+        assert_eq!(nuclei(BoundaryMode::Reflective), [(1, 0)]);
+    }
+
+    #[test]
+    fn periodic_electrons_are_captured_across_the_edge() {
+        let captured = |boundary| {
+            let mut simulation = scene(boundary);
+            let proton = simulation.add_particle(10.0, 300.0, ParticleType::Proton);
+            let electron = simulation.add_particle(960.0, 300.0, ParticleType::Electron);
+            simulation.update();
+            simulation.get_particle(electron).unwrap().orbiting_around == Some(proton)
+        };
+        assert!(captured(BoundaryMode::Periodic));
+        assert!(!captured(BoundaryMode::Reflective));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::renderer::Renderer;
+use web_sys::CanvasRenderingContext2d;
+
+// Backend Canvas2D del navegador
+pub struct CanvasRenderer<'a> {
+    context: &'a CanvasRenderingContext2d,
+}
+
+impl<'a> CanvasRenderer<'a> {
+    pub fn new(context: &'a CanvasRenderingContext2d) -> Self {
+        CanvasRenderer { context }
+    }
+}
+
+impl Renderer for CanvasRenderer<'_> {
+    fn begin_path(&mut self) {
+        self.context.begin_path();
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let _ = self.context.arc(x, y, radius, start_angle, end_angle);
+    }
+
+    fn ellipse(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius_x: f64,
+        radius_y: f64,
+        rotation: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) {
+        let _ = self
+            .context
+            .ellipse(x, y, radius_x, radius_y, rotation, start_angle, end_angle);
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.context.rect(x, y, width, height);
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.context.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        self.context.line_to(x, y);
+    }
+
+    fn stroke(&mut self) {
+        self.context.stroke();
+    }
+
+    fn fill(&mut self) {
+        self.context.fill();
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+        let _ = self.context.fill_text(text, x, y);
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.context.set_fill_style_str(color);
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.context.set_stroke_style_str(color);
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.context.set_line_width(width);
+    }
+
+    fn set_line_dash(&mut self, segments: &[f64]) {
+        let dash_array = js_sys::Array::new();
+        for segment in segments {
+            dash_array.push(&(*segment).into());
+        }
+        let _ = self.context.set_line_dash(&dash_array);
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.context.set_font(font);
+    }
+
+    fn set_text_align(&mut self, align: &str) {
+        self.context.set_text_align(align);
+    }
+
+    fn set_text_baseline(&mut self, baseline: &str) {
+        self.context.set_text_baseline(baseline);
+    }
+}
@@ -0,0 +1,731 @@
+use crate::atomic::electronegativity;
+use crate::events::SimulationEvent;
+use crate::nuclear::decay_channel;
+use crate::particle::{Particle, ParticleId, ParticleType};
+use crate::physics::Physics;
+use crate::simulation::{Simulation, NUCLEUS_CLUSTER_RANGE};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// Distancias de formación y de ruptura, relativas a la longitud de equilibrio
+const BOND_FORMATION_FACTOR: f64 = 1.25;
+const BOND_BREAK_FACTOR: f64 = 2.0;
+
+// Parte de la suma de radios de las nubes electrónicas que separa a dos átomos enlazados
+const CLOUD_OVERLAP: f64 = 0.75;
+
+// Holgura sobre `NUCLEUS_CLUSTER_RANGE`: dos átomos enlazados no deben contarse como un núcleo
+const BOND_MARGIN: f64 = 10.0;
+
+// Con los enlaces activos, los átomos a partir de este Z se mueven en bloque: la repulsión
+// entre sus protones desharía el núcleo antes de que llegara a enlazarse
+pub(crate) const BONDED_NUCLEUS_PROTONS: usize = 2;
+
+const MAX_BOND_ORDER: usize = 3;
+
+// Diferencia de electronegatividad (Pauling) a partir de la cual el electrón cambia de átomo
+const IONIC_ELECTRONEGATIVITY_DIFFERENCE: f64 = 1.7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BondKind {
+    Covalent, // Electrones de valencia compartidos (orden 1-3)
+    // Iones de carga opuesta unidos por el mismo muelle que el covalente: el término de
+    // Coulomb de `physics.rs` es despreciable (~1e-5) y no es lo que los mantiene juntos
+    Ionic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bond {
+    pub atoms: (ParticleId, ParticleId), // Protón de menor id de cada átomo
+    pub kind: BondKind,
+    pub order: usize,
+    pub length: f64, // Distancia de equilibrio entre los centros de masas
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Molecule {
+    pub formula: String,
+    pub atoms: Vec<ParticleId>,
+    pub bonds: usize,
+    pub charge: i32,
+    pub x: f64, // Centro de masas
+    pub y: f64,
+}
+
+// Átomo visto desde la química: núcleo detectado y sus electrones ligados
+pub(crate) struct ChemicalAtom {
+    pub(crate) anchor: ParticleId,
+    pub(crate) protons: usize,
+    neutrons: usize,
+    pub(crate) electrons: usize,
+    bound: Vec<ParticleId>, // Electrones ligados, en orden de llenado
+    nucleons: Vec<usize>,
+    mass: f64,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    extent: f64,
+    cloud: f64, // Distancia al centro del electrón ligado más alejado
+}
+
+impl ChemicalAtom {
+    pub(crate) fn charge(&self) -> i32 {
+        self.protons as i32 - self.electrons as i32
+    }
+
+    // Solo los núcleos estables forman enlaces: uno radiactivo cambiará de elemento
+    fn stable(&self) -> bool {
+        decay_channel(self.protons, self.neutrons, self.electrons > 0).is_none()
+    }
+
+    fn distance_to(&self, other: &ChemicalAtom) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+// Muelle de un enlace listo para `calculate_forces`: índices de los nucleones de cada átomo
+pub(crate) struct BondSpring {
+    a: Vec<usize>,
+    b: Vec<usize>,
+    stiffness: f64,
+    length: f64,
+    screening: f64, // Repulsión protón-protón que se anula entre los dos átomos
+}
+
+impl Simulation {
+    // Enlaces que admite un átomo con `electrons` electrones: los electrones de su capa
+    // más externa según `get_orbital_info`, o los huecos que le faltan para llenarla
+    // (2 en la capa 1, octeto en las demás). H 1, C 4, N 3, O 2, Cl 1, gases nobles 0.
+    pub fn valence(&self, electrons: usize) -> usize {
+        let shells: Vec<usize> = (0..electrons).filter_map(|i| self.get_orbital_info(i)).map(|(_, n, _, _)| n).collect();
+        let Some(&outer) = shells.iter().max() else { return 0 };
+        let valence_electrons = shells.iter().filter(|&&n| n == outer).count();
+        let shell_capacity: usize = if outer == 1 { 2 } else { 8 };
+        valence_electrons.min(shell_capacity.saturating_sub(valence_electrons))
+    }
+
+    pub(crate) fn chemical_atoms(&self) -> Vec<ChemicalAtom> {
+        self.detect_nuclei()
+            .into_iter()
+            .filter_map(|nucleus| {
+                let protons: Vec<ParticleId> = nucleus.protons.iter().map(|&idx| self.particles[idx].id).collect();
+                let anchor = *protons.iter().min()?;
+                let mut electrons = self.electrons_bound_to(&protons);
+                electrons.sort_by_key(|&id| {
+                    let orbital = self.get_particle(id).and_then(|p| p.orbital_position);
+                    (orbital.map_or(usize::MAX, |o| self.orbital_rank(o)), id)
+                });
+                let state = self.cluster_state(&nucleus);
+                let cloud = electrons
+                    .iter()
+                    .filter_map(|&id| self.get_particle(id))
+                    .map(|e| (e.x - state.x).hypot(e.y - state.y))
+                    .fold(0.0, f64::max);
+                Some(ChemicalAtom {
+                    anchor,
+                    protons: protons.len(),
+                    neutrons: nucleus.neutrons.len(),
+                    electrons: electrons.len(),
+                    bound: electrons,
+                    nucleons: nucleus.protons.iter().chain(&nucleus.neutrons).copied().collect(),
+                    mass: state.mass,
+                    x: state.x,
+                    y: state.y,
+                    extent: state.extent,
+                    cloud,
+                })
+            })
+            .collect()
+    }
+
+    // Forma, refuerza y rompe enlaces. Dos átomos cercanos se enlazan de forma iónica si
+    // son iones de carga opuesta y covalente si a ambos les queda valencia libre; los
+    // enlaces covalentes suben de orden (dobles, triples) mientras ambos tengan valencia.
+    // Un enlace se rompe al estirarse demasiado, si desaparece uno de sus átomos o si un
+    // cambio de electrones (ionización...) deja a un átomo sin valencia para sostenerlo.
+    // Tanto el covalente como el iónico se sostienen solo con el muelle de `add_bond_forces`.
+    pub(crate) fn handle_bonds(&mut self) {
+        let atoms = self.chemical_atoms();
+        let by_anchor: HashMap<ParticleId, usize> = atoms.iter().enumerate().map(|(i, atom)| (atom.anchor, i)).collect();
+        let valences: Vec<usize> = atoms.iter().map(|atom| self.valence(atom.electrons)).collect();
+        let free = |atom: usize, kind: BondKind, used: &HashMap<(usize, BondKind), usize>| {
+            let capacity = match kind {
+                BondKind::Covalent => valences[atom],
+                BondKind::Ionic => atoms[atom].charge().unsigned_abs() as usize,
+            };
+            capacity.saturating_sub(used.get(&(atom, kind)).copied().unwrap_or(0))
+        };
+
+        let mut used: HashMap<(usize, BondKind), usize> = HashMap::new();
+        let mut bonds = Vec::new();
+        for mut bond in std::mem::take(&mut self.bonds) {
+            let (Some(&i), Some(&j)) = (by_anchor.get(&bond.atoms.0), by_anchor.get(&bond.atoms.1)) else {
+                self.events.extend(self.bond_breaking(&bond, &atoms));
+                continue;
+            };
+            let (a, b) = (&atoms[i], &atoms[j]);
+            bond.order = bond.order.min(free(i, bond.kind, &used)).min(free(j, bond.kind, &used));
+            let stretched = a.distance_to(b) > bond.length * BOND_BREAK_FACTOR;
+            let neutralized = bond.kind == BondKind::Ionic && a.charge() * b.charge() >= 0;
+            if bond.order == 0 || stretched || neutralized {
+                self.events.push(SimulationEvent::BondBreaking {
+                    bond: bond.kind,
+                    protons: (a.protons, b.protons),
+                    x: (a.x + b.x) / 2.0,
+                    y: (a.y + b.y) / 2.0,
+                    time_step: self.time_step,
+                });
+                continue;
+            }
+            *used.entry((i, bond.kind)).or_default() += bond.order;
+            *used.entry((j, bond.kind)).or_default() += bond.order;
+            bonds.push(bond);
+        }
+
+        // Pares cercanos aún sin enlace, del más próximo al más lejano
+        let bonded: HashSet<(ParticleId, ParticleId)> = bonds.iter().map(|bond| bond.atoms).collect();
+        let mut candidates = Vec::new();
+        for (i, a) in atoms.iter().enumerate() {
+            for (j, b) in atoms.iter().enumerate().skip(i + 1) {
+                let pair = (a.anchor.min(b.anchor), a.anchor.max(b.anchor));
+                if bonded.contains(&pair) || !a.stable() || !b.stable() {
+                    continue;
+                }
+                let length = Self::bond_length(a, b);
+                let distance = a.distance_to(b);
+                if distance < length * BOND_FORMATION_FACTOR {
+                    candidates.push((distance, i, j, pair, length));
+                }
+            }
+        }
+        candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        let first_new = bonds.len();
+        for (_, i, j, atoms_pair, length) in candidates {
+            let ionic = atoms[i].charge() * atoms[j].charge() < 0;
+            let kind = if ionic { BondKind::Ionic } else { BondKind::Covalent };
+            if free(i, kind, &used) == 0 || free(j, kind, &used) == 0 {
+                continue;
+            }
+            *used.entry((i, kind)).or_default() += 1;
+            *used.entry((j, kind)).or_default() += 1;
+            bonds.push(Bond { atoms: atoms_pair, kind, order: 1, length });
+        }
+
+        // Enlaces múltiples con la valencia que sobra
+        for bond in bonds.iter_mut().filter(|bond| bond.kind == BondKind::Covalent) {
+            let (i, j) = (by_anchor[&bond.atoms.0], by_anchor[&bond.atoms.1]);
+            while bond.order < MAX_BOND_ORDER
+                && free(i, BondKind::Covalent, &used) > 0
+                && free(j, BondKind::Covalent, &used) > 0
+            {
+                bond.order += 1;
+                *used.entry((i, BondKind::Covalent)).or_default() += 1;
+                *used.entry((j, BondKind::Covalent)).or_default() += 1;
+            }
+        }
+
+        for bond in &bonds[first_new..] {
+            let (a, b) = (&atoms[by_anchor[&bond.atoms.0]], &atoms[by_anchor[&bond.atoms.1]]);
+            self.events.push(SimulationEvent::BondFormation {
+                bond: bond.kind,
+                order: bond.order,
+                protons: (a.protons, b.protons),
+                x: (a.x + b.x) / 2.0,
+                y: (a.y + b.y) / 2.0,
+                time_step: self.time_step,
+            });
+        }
+        self.bonds = bonds;
+    }
+
+    // Distancia de equilibrio: las nubes se solapan, pero un enlace formado al máximo
+    // estiramiento (r₀·BOND_FORMATION_FACTOR) no rebota hasta dejar nucleones de uno y
+    // otro átomo a menos de `NUCLEUS_CLUSTER_RANGE`, donde contarían como un solo núcleo
+    fn bond_length(a: &ChemicalAtom, b: &ChemicalAtom) -> f64 {
+        let contact = NUCLEUS_CLUSTER_RANGE + a.extent + b.extent + BOND_MARGIN;
+        (CLOUD_OVERLAP * (a.cloud + b.cloud)).max(contact / (2.0 - BOND_FORMATION_FACTOR))
+    }
+
+    // Rompe los enlaces anclados en el protón `id` (antes de eliminarlo de la simulación)
+    pub(crate) fn break_bonds_of(&mut self, id: ParticleId) {
+        if !self.bonds.iter().any(|bond| bond.atoms.0 == id || bond.atoms.1 == id) {
+            return;
+        }
+        let atoms = self.chemical_atoms();
+        let (broken, kept): (Vec<Bond>, Vec<Bond>) =
+            std::mem::take(&mut self.bonds).into_iter().partition(|bond| bond.atoms.0 == id || bond.atoms.1 == id);
+        for bond in &broken {
+            self.events.extend(self.bond_breaking(bond, &atoms));
+        }
+        self.bonds = kept;
+    }
+
+    // Suceso de ruptura de `bond` aunque uno de sus átomos ya no exista: cada extremo es
+    // el átomo que contiene ahora su protón ancla (0 protones si el protón ha desaparecido)
+    fn bond_breaking(&self, bond: &Bond, atoms: &[ChemicalAtom]) -> Option<SimulationEvent> {
+        let end = |anchor: ParticleId| {
+            let idx = self.index_of(anchor)?;
+            atoms.iter().find(|atom| atom.anchor == anchor || atom.nucleons.contains(&idx))
+        };
+        let ends = [end(bond.atoms.0), end(bond.atoms.1)];
+        let found: Vec<&ChemicalAtom> = ends.iter().flatten().copied().collect();
+        if found.is_empty() {
+            return None;
+        }
+        let protons = |atom: Option<&ChemicalAtom>| atom.map_or(0, |atom| atom.protons);
+        Some(SimulationEvent::BondBreaking {
+            bond: bond.kind,
+            protons: (protons(ends[0]), protons(ends[1])),
+            x: found.iter().map(|atom| atom.x).sum::<f64>() / found.len() as f64,
+            y: found.iter().map(|atom| atom.y).sum::<f64>() / found.len() as f64,
+            time_step: self.time_step,
+        })
+    }
+
+    // Transferencia electrónica: cuando las nubes de dos átomos se solapan y sus
+    // electronegatividades difieren lo bastante, el electrón más externo del menos
+    // electronegativo pasa al otro (Na + Cl → Na⁺ + Cl⁻). Solo cede quien tiene valencia
+    // libre y solo acepta quien tiene hueco en su capa externa; cada átomo interviene
+    // como mucho en una transferencia por paso. Ambos átomos deben tener núcleo estable.
+    pub(crate) fn handle_electron_transfers(&mut self) {
+        let atoms = self.chemical_atoms();
+
+        let mut candidates = Vec::new();
+        for (i, a) in atoms.iter().enumerate() {
+            for (j, b) in atoms.iter().enumerate().skip(i + 1) {
+                let distance = a.distance_to(b);
+                if distance >= a.cloud + b.cloud || !a.stable() || !b.stable() {
+                    continue;
+                }
+                let (Some(chi_a), Some(chi_b)) = (electronegativity(a.protons), electronegativity(b.protons)) else {
+                    continue;
+                };
+                let (donor, acceptor) = if chi_a < chi_b { (i, j) } else { (j, i) };
+                if (chi_a - chi_b).abs() >= IONIC_ELECTRONEGATIVITY_DIFFERENCE {
+                    candidates.push((distance, donor, acceptor));
+                }
+            }
+        }
+        candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        let mut involved = HashSet::new();
+        for (_, donor, acceptor) in candidates {
+            let (giver, taker) = (&atoms[donor], &atoms[acceptor]);
+            if involved.contains(&donor)
+                || involved.contains(&acceptor)
+                || self.valence(giver.electrons) == 0
+                || self.valence(taker.electrons) == 0
+            {
+                continue;
+            }
+            let Some((&electron, remaining)) = giver.bound.split_last() else { continue };
+            let Some(donor_core) = self.get_particle(electron).and_then(|e| e.orbiting_around) else { continue };
+            let acceptor_core = taker
+                .bound
+                .first()
+                .and_then(|&id| self.get_particle(id)?.orbiting_around)
+                .unwrap_or(taker.anchor);
+            involved.insert(donor);
+            involved.insert(acceptor);
+
+            // Ambos átomos rehacen su configuración: el electrón ocupa el primer hueco del aceptor
+            let mut received = taker.bound.clone();
+            received.push(electron);
+            self.bind_electrons(remaining, donor_core);
+            self.bind_electrons(&received, acceptor_core);
+
+            self.events.push(SimulationEvent::ElectronTransfer {
+                donor: giver.protons,
+                acceptor: taker.protons,
+                donor_charge: giver.charge() + 1,
+                acceptor_charge: taker.charge() - 1,
+                x: (giver.x + taker.x) / 2.0,
+                y: (giver.y + taker.y) / 2.0,
+                time_step: self.time_step,
+            });
+        }
+    }
+
+    // Un muelle por enlace entre los nucleones de sus dos átomos (índices del paso actual)
+    pub(crate) fn bond_springs(&self) -> Vec<BondSpring> {
+        if self.bonds.is_empty() {
+            return Vec::new();
+        }
+        let atoms = self.chemical_atoms();
+        let by_anchor: HashMap<ParticleId, &ChemicalAtom> = atoms.iter().map(|atom| (atom.anchor, atom)).collect();
+        self.bonds
+            .iter()
+            .filter_map(|bond| {
+                let (a, b) = (by_anchor.get(&bond.atoms.0)?, by_anchor.get(&bond.atoms.1)?);
+                Some(BondSpring {
+                    a: a.nucleons.clone(),
+                    b: b.nucleons.clone(),
+                    stiffness: self.params.bond_stiffness * bond.order as f64,
+                    length: bond.length,
+                    screening: self.params.proton_repulsion,
+                })
+            })
+            .collect()
+    }
+
+    // Potencial armónico ½·k·(r − r₀)² entre los centros de masas de los dos átomos; la
+    // fuerza se reparte entre sus nucleones según su masa para no deformar el núcleo.
+    // Las nubes electrónicas apantallan los núcleos enlazados: se anula la repulsión entre
+    // los protones de uno y otro átomo, que con Z alto (Na-Cl) rompería el enlace.
+    pub(crate) fn add_bond_forces(particles: &[Particle], springs: &[BondSpring], forces: &mut [(f64, f64)]) {
+        let center = |members: &[usize]| {
+            let (mut mass, mut x, mut y) = (0.0, 0.0, 0.0);
+            for &idx in members {
+                let p = &particles[idx];
+                mass += p.mass;
+                x += p.mass * p.x;
+                y += p.mass * p.y;
+            }
+            (mass, x / mass.max(f64::MIN_POSITIVE), y / mass.max(f64::MIN_POSITIVE))
+        };
+
+        for spring in springs {
+            let (mass_a, ax, ay) = center(&spring.a);
+            let (mass_b, bx, by) = center(&spring.b);
+            let (dx, dy) = (bx - ax, by - ay);
+            let distance = dx.hypot(dy).max(1.0);
+            // Positivo = atracción (mismo convenio que el resto de fuerzas)
+            let magnitude = spring.stiffness * (distance - spring.length);
+            let (fx, fy) = (magnitude * dx / distance, magnitude * dy / distance);
+            for (members, mass, sign) in [(&spring.a, mass_a, 1.0), (&spring.b, mass_b, -1.0)] {
+                for &idx in members.iter() {
+                    let share = particles[idx].mass / mass;
+                    forces[idx].0 += sign * fx * share;
+                    forces[idx].1 += sign * fy * share;
+                }
+            }
+
+            for (i, j, distance) in Self::screened_pairs(particles, spring) {
+                let magnitude = spring.screening / (distance + 0.1);
+                let (fx, fy) = (
+                    magnitude * (particles[j].x - particles[i].x) / distance,
+                    magnitude * (particles[j].y - particles[i].y) / distance,
+                );
+                forces[i].0 += fx;
+                forces[i].1 += fy;
+                forces[j].0 -= fx;
+                forces[j].1 -= fy;
+            }
+        }
+    }
+
+    // Pares (protón de a, protón de b, distancia) cuya repulsión apantalla el enlace
+    fn screened_pairs<'a>(particles: &'a [Particle], spring: &'a BondSpring) -> impl Iterator<Item = (usize, usize, f64)> + 'a {
+        let protons = |members: &'a [usize]| members.iter().copied().filter(|&idx| particles[idx].ptype == ParticleType::Proton);
+        protons(&spring.a).flat_map(move |i| {
+            protons(&spring.b).filter_map(move |j| {
+                let distance = (particles[j].x - particles[i].x).hypot(particles[j].y - particles[i].y);
+                (distance > 0.0).then_some((i, j, distance))
+            })
+        })
+    }
+
+    // Energía potencial almacenada en los enlaces, incluida la repulsión apantallada
+    pub(crate) fn bond_energy(&self) -> f64 {
+        let springs = self.bond_springs();
+        let mut energy = 0.0;
+        for spring in &springs {
+            let center = |members: &[usize]| {
+                let mass: f64 = members.iter().map(|&idx| self.particles[idx].mass).sum();
+                let x: f64 = members.iter().map(|&idx| self.particles[idx].mass * self.particles[idx].x).sum();
+                let y: f64 = members.iter().map(|&idx| self.particles[idx].mass * self.particles[idx].y).sum();
+                (x / mass, y / mass)
+            };
+            let ((ax, ay), (bx, by)) = (center(&spring.a), center(&spring.b));
+            let stretch = (bx - ax).hypot(by - ay) - spring.length;
+            energy += 0.5 * spring.stiffness * stretch * stretch;
+            for (_, _, distance) in Self::screened_pairs(&self.particles, spring) {
+                energy -= Physics::calculate_repulsion_potential(distance, spring.screening);
+            }
+        }
+        energy
+    }
+
+    // Moléculas: componentes conexas de dos o más átomos unidos por enlaces
+    pub fn detect_molecules(&self) -> Vec<Molecule> {
+        if self.bonds.is_empty() {
+            return Vec::new();
+        }
+        let atoms = self.chemical_atoms();
+        let by_anchor: HashMap<ParticleId, usize> = atoms.iter().enumerate().map(|(i, atom)| (atom.anchor, i)).collect();
+
+        // Unión-búsqueda sobre los átomos
+        let mut parent: Vec<usize> = (0..atoms.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        let mut bond_counts: HashMap<usize, usize> = HashMap::new();
+        let mut links = Vec::new();
+        for bond in &self.bonds {
+            if let (Some(&i), Some(&j)) = (by_anchor.get(&bond.atoms.0), by_anchor.get(&bond.atoms.1)) {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri] = rj;
+                links.push(i);
+            }
+        }
+        for i in links {
+            *bond_counts.entry(root(&mut parent, i)).or_default() += 1;
+        }
+
+        let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+        for i in 0..atoms.len() {
+            let r = root(&mut parent, i);
+            match groups.iter_mut().find(|(group, _)| *group == r) {
+                Some((_, members)) => members.push(i),
+                None => groups.push((r, vec![i])),
+            }
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .map(|(r, members)| {
+                let mass: f64 = members.iter().map(|&i| atoms[i].mass).sum();
+                let x = members.iter().map(|&i| atoms[i].mass * atoms[i].x).sum::<f64>() / mass;
+                let y = members.iter().map(|&i| atoms[i].mass * atoms[i].y).sum::<f64>() / mass;
+                let member_atoms: Vec<&ChemicalAtom> = members.iter().map(|&i| &atoms[i]).collect();
+                Molecule {
+                    formula: Self::formula(&member_atoms),
+                    atoms: members.iter().map(|&i| atoms[i].anchor).collect(),
+                    bonds: bond_counts.get(&r).copied().unwrap_or(0),
+                    charge: members.iter().map(|&i| atoms[i].charge()).sum(),
+                    x,
+                    y,
+                }
+            })
+            .collect()
+    }
+
+    // Notación de Hill (C, H y el resto por orden alfabético; sin carbono, todo
+    // alfabético) con los cationes delante, como en NaCl
+    fn formula(atoms: &[&ChemicalAtom]) -> String {
+        let mut counts: Vec<(&str, usize, bool)> = Vec::new();
+        for atom in atoms {
+            let symbol = Self::get_element_symbol(atom.protons);
+            match counts.iter_mut().find(|(s, _, _)| *s == symbol) {
+                Some((_, count, cation)) => {
+                    *count += 1;
+                    *cation |= atom.charge() > 0;
+                }
+                None => counts.push((symbol, 1, atom.charge() > 0)),
+            }
+        }
+        let has_carbon = counts.iter().any(|(s, _, _)| *s == "C");
+        counts.sort_by_key(|&(symbol, _, cation)| {
+            let hill = match symbol {
+                "C" if has_carbon => 0,
+                "H" if has_carbon => 1,
+                _ => 2,
+            };
+            (!cation, hill, symbol)
+        });
+        counts
+            .iter()
+            .map(|(symbol, count, _)| if *count > 1 { format!("{}{}", symbol, count) } else { symbol.to_string() })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: (usize, usize) = (1, 0);
+    const C: (usize, usize) = (6, 6);
+    const O: (usize, usize) = (8, 8);
+    const NA: (usize, usize) = (11, 12);
+    const CL: (usize, usize) = (17, 18);
+
+    // Separaciones iniciales, relativas a r₀, desde las que debe formarse y sostenerse el enlace
+    const SEPARATIONS: [f64; 4] = [0.9, 1.0, 1.1, 1.2];
+
+    // Átomos neutros ((Z, N), x) en reposo con el centro de masas del núcleo en (x, 300)
+    fn scene(atoms: &[((usize, usize), f64)]) -> Simulation {
+        let mut simulation = Simulation::new(1200.0, 600.0);
+        simulation.params.bonding_enabled = true;
+        simulation.params.electron_transfer_enabled = true;
+        for &((protons, neutrons), x) in atoms {
+            let first = simulation.particles.len();
+            simulation.add_nucleus(x, 300.0, protons, neutrons, protons);
+            let added = &mut simulation.particles[first..];
+            let (mass, moment) = added
+                .iter()
+                .filter(|p| p.ptype != ParticleType::Electron)
+                .fold((0.0, 0.0), |(mass, moment), p| (mass + p.mass, moment + p.mass * p.x));
+            let shift = x - moment / mass;
+            for particle in added {
+                particle.x += shift;
+            }
+        }
+        simulation
+    }
+
+    // r₀ entre dos átomos neutros, medida con ambos bien separados
+    fn equilibrium(a: (usize, usize), b: (usize, usize)) -> f64 {
+        let atoms = scene(&[(a, 200.0), (b, 1000.0)]).chemical_atoms();
+        Simulation::bond_length(&atoms[0], &atoms[1])
+    }
+
+    // Fórmulas tras `steps` pasos y enlaces rotos por el camino
+    fn run(simulation: &mut Simulation, steps: usize) -> (Vec<String>, usize) {
+        let mut broken = 0;
+        for _ in 0..steps {
+            simulation.update();
+            broken += simulation
+                .take_events()
+                .iter()
+                .filter(|event| matches!(event, SimulationEvent::BondBreaking { .. }))
+                .count();
+        }
+        (simulation.detect_molecules().into_iter().map(|molecule| molecule.formula).collect(), broken)
+    }
+
+    #[test]
+    fn hydrogen_molecule() {
+        let length = equilibrium(H, H);
+        for factor in SEPARATIONS {
+            let half = factor * length / 2.0;
+            let mut simulation = scene(&[(H, 500.0 - half), (H, 500.0 + half)]);
+            assert_eq!(run(&mut simulation, 400), (vec!["H2".to_string()], 0), "r = {}·r₀", factor);
+            assert_eq!(simulation.bonds[0].order, 1);
+        }
+    }
+
+    #[test]
+    fn water_molecule() {
+        let length = equilibrium(O, H);
+        for factor in SEPARATIONS {
+            let r = factor * length;
+            let mut simulation = scene(&[(O, 600.0), (H, 600.0 - r), (H, 600.0 + r)]);
+            assert_eq!(run(&mut simulation, 400), (vec!["H2O".to_string()], 0), "r = {}·r₀", factor);
+            assert_eq!(simulation.bonds.len(), 2);
+        }
+    }
+
+    #[test]
+    fn carbon_dioxide_has_two_double_bonds() {
+        let length = equilibrium(C, O);
+        for factor in SEPARATIONS {
+            let r = factor * length;
+            let mut simulation = scene(&[(C, 600.0), (O, 600.0 - r), (O, 600.0 + r)]);
+            assert_eq!(run(&mut simulation, 400), (vec!["CO2".to_string()], 0), "r = {}·r₀", factor);
+            assert!(simulation.bonds.iter().all(|bond| bond.kind == BondKind::Covalent && bond.order == 2));
+        }
+    }
+
+    #[test]
+    fn sodium_chloride_is_ionic() {
+        let mut simulation = scene(&[(NA, 450.0), (CL, 750.0)]);
+        assert_eq!(run(&mut simulation, 400), (vec!["NaCl".to_string()], 0));
+        assert_eq!(simulation.bonds[0].kind, BondKind::Ionic);
+        assert!(simulation.detect_molecules()[0].charge == 0);
+    }
+
+    #[test]
+    fn sodium_gives_its_electron_to_chlorine() {
+        let mut simulation = scene(&[(NA, 450.0), (CL, 750.0)]);
+        simulation.update();
+        let transfers: Vec<SimulationEvent> = simulation
+            .take_events()
+            .into_iter()
+            .filter(|event| matches!(event, SimulationEvent::ElectronTransfer { .. }))
+            .collect();
+        assert!(matches!(
+            transfers[..],
+            [SimulationEvent::ElectronTransfer { donor: 11, acceptor: 17, donor_charge: 1, acceptor_charge: -1, .. }]
+        ));
+
+        let mut charges: Vec<(usize, i32, usize)> = simulation
+            .chemical_atoms()
+            .iter()
+            .map(|atom| (atom.protons, atom.charge(), atom.electrons))
+            .collect();
+        charges.sort();
+        assert_eq!(charges, [(11, 1, 10), (17, -1, 18)]);
+    }
+
+    #[test]
+    fn ionic_bond_pulls_the_ions_back_together() {
+        let mut simulation = scene(&[(NA, 450.0), (CL, 750.0)]);
+        simulation.update();
+        let events = simulation.take_events();
+        assert!(events.iter().any(|event| matches!(event, SimulationEvent::ElectronTransfer { .. })));
+        assert!(events.iter().any(|event| matches!(event, SimulationEvent::BondFormation { bond: BondKind::Ionic, .. })));
+        let length = simulation.bonds[0].length;
+
+        // Los iones se separan a v = 1 cada uno: el muelle los frena y los devuelve
+        for particle in simulation.particles.iter_mut().filter(|p| p.ptype != ParticleType::Electron) {
+            particle.dx = if particle.x < 600.0 { -1.0 } else { 1.0 };
+        }
+        let distance = |simulation: &Simulation| {
+            let atoms = simulation.chemical_atoms();
+            atoms[0].distance_to(&atoms[1])
+        };
+        let (mut farthest, mut closest) = (0.0, f64::MAX);
+        for _ in 0..300 {
+            simulation.update();
+            farthest = f64::max(farthest, distance(&simulation));
+            closest = f64::min(closest, distance(&simulation));
+        }
+        assert!(farthest > length + 20.0 && farthest < length * BOND_BREAK_FACTOR);
+        assert!(closest < length);
+        assert_eq!(simulation.bonds.len(), 1);
+        assert_eq!(simulation.detect_molecules()[0].formula, "NaCl");
+    }
+
+    #[test]
+    fn radioactive_sodium_keeps_its_electron() {
+        // Na-22 (β⁺, 2.6 años)
+        let mut simulation = scene(&[((11, 11), 450.0), (CL, 750.0)]);
+        simulation.update();
+        assert!(!simulation.take_events().iter().any(|event| matches!(event, SimulationEvent::ElectronTransfer { .. })));
+        assert!(simulation.chemical_atoms().iter().all(|atom| atom.charge() == 0));
+    }
+
+    #[test]
+    fn unstable_nuclei_do_not_bond() {
+        // Tritio (β⁻, 12 años): mismos electrones que el hidrógeno pero núcleo radiactivo
+        let mut simulation = scene(&[((1, 2), 420.0), (H, 580.0)]);
+        assert!(run(&mut simulation, 10).0.is_empty());
+    }
+
+    #[test]
+    fn removing_an_atom_breaks_its_bonds() {
+        let mut simulation = scene(&[(H, 420.0), (H, 580.0)]);
+        run(&mut simulation, 5);
+        let anchor = simulation.bonds[0].atoms.1;
+        simulation.remove_particle(anchor);
+        assert!(simulation.bonds.is_empty());
+        let events = simulation.take_events();
+        assert!(matches!(
+            events[..],
+            [SimulationEvent::BondBreaking { bond: BondKind::Covalent, protons: (1, 1), .. }]
+        ));
+
+        let mut cleared = scene(&[(H, 420.0), (H, 580.0)]);
+        run(&mut cleared, 5);
+        cleared.clear();
+        assert!(cleared.bonds.is_empty());
+    }
+
+    #[test]
+    fn atoms_move_freely_without_bonding() {
+        // Solo se retienen los núcleos mientras los enlaces (o la fisión) están activos
+        let mut simulation = scene(&[(O, 600.0)]);
+        simulation.update();
+        assert_eq!(simulation.held_nuclei.len(), 1);
+        simulation.params.bonding_enabled = false;
+        simulation.update();
+        assert!(simulation.held_nuclei.is_empty());
+    }
+}
@@ -0,0 +1,460 @@
+use crate::events::SimulationEvent;
+use crate::nuclear::{DecayChannel, DecayMode, FREE_NEUTRON_HALF_LIFE};
+use crate::particle::{Particle, ParticleId, ParticleType};
+use crate::photon::PHOTON_SPEED;
+use crate::simulation::{Simulation, ELECTRON_CAPTURE_RANGE, NUCLEUS_CLUSTER_RANGE};
+
+// Energía liberada por el neutrón libre (MeV)
+const FREE_NEUTRON_DECAY_ENERGY: f64 = 0.782;
+
+// Separación de los nucleones de la partícula α respecto a su centro
+const ALPHA_CLUSTER_RADIUS: f64 = 15.0;
+
+// Núcleo elegido para desintegrarse en este paso (por ids: los índices cambian al modificar partículas)
+struct PendingDecay {
+    channel: DecayChannel,
+    protons: Vec<ParticleId>,
+    neutrons: Vec<ParticleId>,
+    electrons: Vec<ParticleId>,
+}
+
+impl Simulation {
+    // Cada núcleo inestable se desintegra con probabilidad 1 - 2^(-Δt/T½) por paso,
+    // donde Δt = dt · decay_time_scale son los segundos físicos que representa el paso
+    pub(crate) fn handle_decays(&mut self) {
+        let elapsed = self.params.dt * self.params.decay_time_scale;
+        let probability = |half_life: f64| 1.0 - (-elapsed * std::f64::consts::LN_2 / half_life).exp();
+
+        let nuclei = self.detect_nuclei();
+        let mut in_nucleus = vec![false; self.particles.len()];
+        let mut pending = Vec::new();
+
+        for nucleus in &nuclei {
+            for &idx in nucleus.protons.iter().chain(&nucleus.neutrons) {
+                in_nucleus[idx] = true;
+            }
+            let Some(channel) = nucleus.decay_channel() else {
+                continue;
+            };
+            if self.rng.next_f64() < probability(channel.half_life) {
+                let ids = |indices: &[usize]| indices.iter().map(|&idx| self.particles[idx].id).collect();
+                pending.push(PendingDecay {
+                    channel,
+                    protons: ids(&nucleus.protons),
+                    neutrons: ids(&nucleus.neutrons),
+                    electrons: ids(&nucleus.electrons),
+                });
+            }
+        }
+
+        // Neutrones libres: β- con la semivida del neutrón
+        let free_neutrons: Vec<ParticleId> = self
+            .particles
+            .iter()
+            .enumerate()
+            .filter(|(idx, p)| p.ptype == ParticleType::Neutron && !in_nucleus[*idx] && p.orbiting_around.is_none())
+            .map(|(_, p)| p.id)
+            .collect();
+        for id in free_neutrons {
+            if self.rng.next_f64() < probability(FREE_NEUTRON_HALF_LIFE) {
+                pending.push(PendingDecay {
+                    channel: DecayChannel {
+                        mode: DecayMode::BetaMinus,
+                        half_life: FREE_NEUTRON_HALF_LIFE,
+                        energy: FREE_NEUTRON_DECAY_ENERGY,
+                    },
+                    protons: Vec::new(),
+                    neutrons: vec![id],
+                    electrons: Vec::new(),
+                });
+            }
+        }
+
+        for decay in pending {
+            self.apply_decay(decay);
+        }
+    }
+
+    fn apply_decay(&mut self, decay: PendingDecay) {
+        let PendingDecay { channel, mut protons, mut neutrons, electrons } = decay;
+        let (parent_protons, parent_neutrons) = (protons.len(), neutrons.len());
+        let (x, y) = self.center_of(protons.iter().chain(&neutrons));
+
+        match channel.mode {
+            DecayMode::BetaMinus => {
+                // Un neutrón pasa a protón; el electrón se lleva una fracción aleatoria
+                // de Q (el resto se lo lleva el antineutrino, que no se simula)
+                let Some(id) = neutrons.pop() else { return };
+                self.convert_nucleon(id, ParticleType::Proton);
+                protons.push(id);
+                let fraction = self.rng.next_f64();
+                self.emit(ParticleType::Electron, &protons, &neutrons, channel.energy * fraction);
+            }
+            DecayMode::BetaPlus => {
+                let Some(id) = protons.pop() else { return };
+                self.convert_nucleon(id, ParticleType::Neutron);
+                self.reassign_orbiters(id, protons.first().copied());
+                neutrons.push(id);
+                let fraction = self.rng.next_f64();
+                self.emit(ParticleType::Positron, &protons, &neutrons, channel.energy * fraction);
+            }
+            DecayMode::ElectronCapture => {
+                // El núcleo absorbe uno de sus electrones; solo escapa el neutrino
+                let Some(electron) = electrons.iter().copied().find(|&id| self.index_of(id).is_some()) else { return };
+                let Some(id) = protons.pop() else { return };
+                self.remove_particle(electron);
+                self.convert_nucleon(id, ParticleType::Neutron);
+                self.reassign_orbiters(id, protons.first().copied());
+                neutrons.push(id);
+            }
+            DecayMode::NeutronEmission => {
+                let Some(id) = self.outermost(&neutrons, x, y) else { return };
+                neutrons.retain(|&n| n != id);
+                self.eject(&[id], &protons, &neutrons, channel.energy);
+            }
+            DecayMode::Alpha => {
+                let (Some(p1), Some(p2)) = (protons.pop(), protons.pop()) else { return };
+                let (Some(n1), Some(n2)) = (neutrons.pop(), neutrons.pop()) else { return };
+                let alpha = [p1, n1, p2, n2];
+                for &id in &[p1, p2] {
+                    self.reassign_orbiters(id, protons.first().copied());
+                }
+                self.eject(&alpha, &protons, &neutrons, channel.energy);
+            }
+        }
+
+        self.events.push(SimulationEvent::Decay {
+            mode: channel.mode,
+            parent_protons,
+            parent_neutrons,
+            daughter_protons: protons.len(),
+            daughter_neutrons: neutrons.len(),
+            energy: channel.energy,
+            x,
+            y,
+            time_step: self.time_step,
+        });
+    }
+
+    // Cambia un nucleón de tipo conservando posición, velocidad y masa
+    fn convert_nucleon(&mut self, id: ParticleId, ptype: ParticleType) {
+        if let Some(idx) = self.index_of(id) {
+            let template = Particle::new(0.0, 0.0, ptype, 0.0);
+            let particle = &mut self.particles[idx];
+            particle.ptype = ptype;
+            particle.charge = template.charge;
+            particle.radius = template.radius;
+            particle.orbiting_around = None; // Los protones no orbitan; un neutrón nuevo se recaptura solo
+        }
+    }
+
+    // Las partículas que orbitaban `from` pasan a orbitar `to` (o quedan libres)
+    fn reassign_orbiters(&mut self, from: ParticleId, to: Option<ParticleId>) {
+        for particle in &mut self.particles {
+            if particle.orbiting_around == Some(from) {
+                particle.orbiting_around = to;
+            }
+        }
+    }
+
+    fn center_of<'a>(&self, ids: impl Iterator<Item = &'a ParticleId>) -> (f64, f64) {
+        let (mut x, mut y, mut count) = (0.0, 0.0, 0.0);
+        for particle in ids.filter_map(|&id| self.get_particle(id)) {
+            x += particle.x;
+            y += particle.y;
+            count += 1.0;
+        }
+        if count > 0.0 {
+            (x / count, y / count)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    fn outermost(&self, ids: &[ParticleId], x: f64, y: f64) -> Option<ParticleId> {
+        ids.iter()
+            .filter_map(|&id| self.get_particle(id))
+            .max_by(|a, b| {
+                let da = (a.x - x).hypot(a.y - y);
+                let db = (b.x - x).hypot(b.y - y);
+                da.total_cmp(&db)
+            })
+            .map(|p| p.id)
+    }
+
+    // Crea un leptón (e⁻ o e⁺) que sale del núcleo con energía cinética `energy` (MeV)
+    fn emit(&mut self, ptype: ParticleType, protons: &[ParticleId], neutrons: &[ParticleId], energy: f64) {
+        let mass = self.params.electron_mass;
+        let daughter: Vec<ParticleId> = protons.iter().chain(neutrons).copied().collect();
+        let emission = self.emission(&daughter, &[], mass, energy);
+
+        // Fuera del radio de captura para que no quede ligado al instante
+        let (x, y) = emission.exit_point(ELECTRON_CAPTURE_RANGE + 10.0);
+        let mut particle = Particle::new(x, y, ptype, mass);
+        particle.dx = emission.velocity.0;
+        particle.dy = emission.velocity.1;
+        self.push_particle(particle);
+    }
+
+    // Expulsa nucleones ya existentes (neutrón o partícula α). El efecto túnel se
+    // modela colocándolos directamente fuera del núcleo hijo, más allá del radio con
+    // el que `detect_nuclei` los volvería a contar como parte de él.
+    fn eject(&mut self, ejected: &[ParticleId], protons: &[ParticleId], neutrons: &[ParticleId], energy: f64) {
+        let mass: f64 = ejected.iter().filter_map(|&id| self.get_particle(id)).map(|p| p.mass).sum();
+        self.release_nucleons(ejected);
+        let daughter: Vec<ParticleId> = protons.iter().chain(neutrons).copied().collect();
+        let emission = self.emission(&daughter, ejected, mass, energy);
+        let (ex, ey) = emission.exit_point(NUCLEUS_CLUSTER_RANGE + ALPHA_CLUSTER_RADIUS + 10.0);
+
+        let count = ejected.len();
+        for (k, &id) in ejected.iter().enumerate() {
+            let Some(idx) = self.index_of(id) else { continue };
+            // Varios nucleones: en anillo compacto alrededor del punto de salida
+            let (ox, oy) = if count > 1 {
+                let angle = k as f64 * 2.0 * std::f64::consts::PI / count as f64;
+                (angle.cos() * ALPHA_CLUSTER_RADIUS, angle.sin() * ALPHA_CLUSTER_RADIUS)
+            } else {
+                (0.0, 0.0)
+            };
+            let particle = &mut self.particles[idx];
+            particle.x = ex + ox;
+            particle.y = ey + oy;
+            particle.dx = emission.velocity.0;
+            particle.dy = emission.velocity.1;
+            particle.orbiting_around = None;
+        }
+        // Lo que orbitaba a un nucleón expulsado (neutrones del hijo) se recaptura solo
+        for &id in ejected {
+            self.reassign_orbiters(id, None);
+        }
+    }
+
+    // Reparte Q entre la partícula emitida (masa `mass`) y el núcleo hijo conservando
+    // el momento: la emitida recibe la fracción M/(m + M) de la energía y el hijo
+    // retrocede en sentido contrario. La dirección de salida es aleatoria.
+    fn emission(&mut self, daughter: &[ParticleId], ejected: &[ParticleId], mass: f64, energy: f64) -> Emission {
+        let daughter: Vec<usize> = daughter.iter().filter_map(|&id| self.index_of(id)).collect();
+        // Sin núcleo hijo (neutrón libre) se parte de la propia partícula emitida
+        let reference: Vec<usize> = if daughter.is_empty() {
+            ejected.iter().filter_map(|&id| self.index_of(id)).collect()
+        } else {
+            daughter.clone()
+        };
+
+        let (mut total_mass, mut cx, mut cy, mut px, mut py) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for &idx in &reference {
+            let p = &self.particles[idx];
+            total_mass += p.mass;
+            cx += p.mass * p.x;
+            cy += p.mass * p.y;
+            px += p.mass * p.dx;
+            py += p.mass * p.dy;
+        }
+        if total_mass > 0.0 {
+            cx /= total_mass;
+            cy /= total_mass;
+            px /= total_mass;
+            py /= total_mass;
+        }
+        let extent = reference
+            .iter()
+            .map(|&idx| {
+                let p = &self.particles[idx];
+                (p.x - cx).hypot(p.y - cy) + p.radius
+            })
+            .fold(0.0, f64::max);
+
+        let angle = self.rng.next_angle();
+        let direction = (angle.cos(), angle.sin());
+        let kinetic = energy.max(0.0) * self.params.nuclear_energy_scale;
+        let daughter_mass: f64 = daughter.iter().map(|&idx| self.particles[idx].mass).sum();
+
+        let speed = if mass <= 0.0 {
+            // Sin masa (p. ej. electron_mass = 0) no hay v = √(2E/m): sale a velocidad fija sin retroceso
+            PHOTON_SPEED
+        } else if daughter_mass > 0.0 {
+            let speed = (2.0 * kinetic * daughter_mass / (mass * (mass + daughter_mass))).sqrt();
+            let recoil = mass * speed / daughter_mass;
+            for &idx in &daughter {
+                self.particles[idx].dx -= direction.0 * recoil;
+                self.particles[idx].dy -= direction.1 * recoil;
+            }
+            speed
+        } else {
+            (2.0 * kinetic / mass).sqrt()
+        };
+
+        Emission {
+            center: (cx, cy),
+            extent,
+            direction,
+            velocity: (px + direction.0 * speed, py + direction.1 * speed),
+        }
+    }
+}
+
+struct Emission {
+    center: (f64, f64), // Centro de masas del núcleo hijo
+    extent: f64,        // Radio del núcleo hijo
+    direction: (f64, f64),
+    velocity: (f64, f64), // Velocidad final de la partícula emitida
+}
+
+impl Emission {
+    // Punto de salida a `margin` de la superficie del núcleo hijo
+    fn exit_point(&self, margin: f64) -> (f64, f64) {
+        let reach = self.extent + margin;
+        (self.center.0 + self.direction.0 * reach, self.center.1 + self.direction.1 * reach)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Núcleo de 5 protones y ningún neutrón (muy inestable, β+) con un paso que
+    // representa muchos segundos: se desintegra en el primer `update`
+    fn boron_5(electron_mass: f64) -> Simulation {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.params.decay_enabled = true;
+        simulation.params.decay_time_scale = 1e6;
+        simulation.params.electron_mass = electron_mass;
+        simulation.add_nucleus(400.0, 300.0, 5, 0, 0);
+        simulation
+    }
+
+    fn decays(simulation: &mut Simulation) -> Vec<SimulationEvent> {
+        simulation.update();
+        simulation.take_events().into_iter().filter(|e| matches!(e, SimulationEvent::Decay { .. })).collect()
+    }
+
+    #[test]
+    fn unstable_nucleus_decays_at_accelerated_time_scale() {
+        let mut simulation = boron_5(0.01);
+        let events = decays(&mut simulation);
+        assert!(!events.is_empty());
+        let SimulationEvent::Decay { mode, parent_protons, parent_neutrons, daughter_protons, daughter_neutrons, .. } = events[0] else {
+            unreachable!()
+        };
+        assert_eq!(mode, DecayMode::BetaPlus);
+        assert_eq!((daughter_protons, daughter_neutrons), (parent_protons - 1, parent_neutrons + 1));
+        assert!(simulation.particles.iter().any(|p| p.ptype == ParticleType::Positron));
+    }
+
+    #[test]
+    fn nothing_decays_at_physical_time_scale() {
+        let mut simulation = boron_5(0.01);
+        simulation.params.decay_time_scale = 1e-30;
+        assert!(decays(&mut simulation).is_empty());
+    }
+
+    #[test]
+    fn massless_leptons_get_finite_velocities() {
+        let mut simulation = boron_5(0.0);
+        assert!(!decays(&mut simulation).is_empty());
+        for _ in 0..5 {
+            simulation.update();
+        }
+        for particle in &simulation.particles {
+            assert!(particle.dx.is_finite() && particle.dy.is_finite(), "{:?}", particle);
+            assert!(particle.x.is_finite() && particle.y.is_finite(), "{:?}", particle);
+        }
+    }
+
+    // Átomo (Z, N) recién construido, con una escala de tiempo que desintegra cualquier
+    // núcleo de la tabla; se desintegra antes de que las fuerzas muevan sus nucleones
+    fn atom(protons: usize, neutrons: usize, electrons: usize) -> Simulation {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.params.decay_enabled = true;
+        simulation.params.decay_time_scale = 1e12;
+        simulation.add_nucleus(400.0, 300.0, protons, neutrons, electrons);
+        simulation.handle_decays();
+        simulation
+    }
+
+    fn decay_events(simulation: &mut Simulation) -> Vec<SimulationEvent> {
+        simulation.take_events().into_iter().filter(|e| matches!(e, SimulationEvent::Decay { .. })).collect()
+    }
+
+    // Átomo neutro y su único suceso de desintegración
+    fn single_decay(protons: usize, neutrons: usize) -> (Simulation, SimulationEvent) {
+        let mut simulation = atom(protons, neutrons, protons);
+        let events = decay_events(&mut simulation);
+        assert_eq!(events.len(), 1, "{:?}", events);
+        (simulation, events[0].clone())
+    }
+
+    fn daughter(event: &SimulationEvent) -> (DecayMode, usize, usize) {
+        let SimulationEvent::Decay { mode, daughter_protons, daughter_neutrons, .. } = *event else { unreachable!() };
+        (mode, daughter_protons, daughter_neutrons)
+    }
+
+    // (Z, N) de cada núcleo detectado, ordenados
+    fn nuclei(simulation: &Simulation) -> Vec<(usize, usize)> {
+        let mut found: Vec<(usize, usize)> =
+            simulation.detect_nuclei().iter().map(|n| (n.protons.len(), n.neutrons.len())).collect();
+        found.sort_unstable();
+        found
+    }
+
+    // Electrones (ligados a un protón existente, libres)
+    fn electrons(simulation: &Simulation) -> (usize, usize) {
+        let electrons = simulation.particles.iter().filter(|p| p.ptype == ParticleType::Electron);
+        let bound = electrons
+            .clone()
+            .filter(|e| e.orbiting_around.and_then(|id| simulation.get_particle(id)).is_some_and(|p| p.ptype == ParticleType::Proton))
+            .count();
+        (bound, electrons.count() - bound)
+    }
+
+    #[test]
+    fn alpha_decay_ejects_a_helium_nucleus() {
+        // Be-8 → He-4 + α: los cuatro electrones se quedan con el hijo
+        let (simulation, event) = single_decay(4, 4);
+        assert_eq!(daughter(&event), (DecayMode::Alpha, 2, 2));
+        assert_eq!(nuclei(&simulation), [(2, 2), (2, 2)]);
+        assert_eq!(electrons(&simulation), (4, 0));
+    }
+
+    #[test]
+    fn beta_minus_turns_a_neutron_into_a_proton() {
+        // He-6 → Li-6 + e⁻: el electrón emitido sale libre y los ligados siguen ligados
+        let (simulation, event) = single_decay(2, 4);
+        assert_eq!(daughter(&event), (DecayMode::BetaMinus, 3, 3));
+        assert_eq!(nuclei(&simulation), [(3, 3)]);
+        assert_eq!(electrons(&simulation), (2, 1));
+        let emitted = simulation.particles.iter().find(|p| p.ptype == ParticleType::Electron && p.orbiting_around.is_none());
+        assert!(emitted.is_some_and(|e| e.dx.hypot(e.dy) > 0.0));
+    }
+
+    #[test]
+    fn electron_capture_absorbs_a_bound_electron() {
+        // Be-7 → Li-7: el núcleo se queda uno de sus electrones y el resto sigue ligado
+        let (simulation, event) = single_decay(4, 3);
+        assert_eq!(daughter(&event), (DecayMode::ElectronCapture, 3, 4));
+        assert_eq!(nuclei(&simulation), [(3, 4)]);
+        assert_eq!(electrons(&simulation), (3, 0));
+
+        // Sin electrones no hay nada que capturar
+        assert!(decay_events(&mut atom(4, 3, 0)).is_empty());
+    }
+
+    #[test]
+    fn neutron_emission_ejects_a_free_neutron() {
+        // He-5 → He-4 + n
+        let (simulation, event) = single_decay(2, 3);
+        assert_eq!(daughter(&event), (DecayMode::NeutronEmission, 2, 2));
+        assert_eq!(nuclei(&simulation), [(2, 2)]);
+        assert_eq!(electrons(&simulation), (2, 0));
+        let in_nucleus: Vec<usize> = simulation.detect_nuclei().iter().flat_map(|n| n.neutrons.clone()).collect();
+        let free: Vec<&Particle> = simulation
+            .particles
+            .iter()
+            .enumerate()
+            .filter(|(idx, p)| p.ptype == ParticleType::Neutron && !in_nucleus.contains(idx))
+            .map(|(_, p)| p)
+            .collect();
+        assert_eq!(free.len(), 1);
+        assert!(free[0].orbiting_around.is_none() && free[0].dx.hypot(free[0].dy) > 0.0);
+    }
+}
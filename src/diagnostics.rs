@@ -0,0 +1,160 @@
+use crate::boundary::BoundaryMode;
+use crate::particle::{Particle, ParticleType};
+use crate::physics::Physics;
+use crate::simulation::Simulation;
+use crate::spatial::SpatialGrid;
+use serde::Serialize;
+
+// Magnitudes conservadas del sistema en un instante, para seguir su deriva
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Diagnostics {
+    pub kinetic_energy: f64,
+    pub coulomb_energy: f64,
+    pub nuclear_energy: f64,
+    pub repulsion_energy: f64, // Repulsiones p-p y e-e
+    pub bond_energy: f64,      // Muelles de los enlaces químicos
+    pub field_energy: f64,     // Cargas en el campo eléctrico externo
+    pub total_energy: f64,
+    pub momentum_x: f64,
+    pub momentum_y: f64,
+    pub angular_momentum: f64, // Respecto al centro de masas (componente z)
+    pub temperature: f64,      // Temperatura cinética de las partículas libres
+}
+
+impl Simulation {
+    pub fn diagnostics(&self) -> Diagnostics {
+        let particles = &self.particles;
+        let mut d = Diagnostics::default();
+
+        // Cinética, momento lineal y centro de masas
+        let (mut total_mass, mut cm_x, mut cm_y) = (0.0, 0.0, 0.0);
+        for p in particles {
+            d.kinetic_energy += 0.5 * p.mass * (p.dx * p.dx + p.dy * p.dy);
+            d.momentum_x += p.mass * p.dx;
+            d.momentum_y += p.mass * p.dy;
+            total_mass += p.mass;
+            cm_x += p.mass * p.x;
+            cm_y += p.mass * p.y;
+        }
+        if total_mass > 0.0 {
+            cm_x /= total_mass;
+            cm_y /= total_mass;
+        }
+        for p in particles {
+            d.angular_momentum += p.mass * ((p.x - cm_x) * p.dy - (p.y - cm_y) * p.dx);
+        }
+
+        // Potencial nuclear: solo pares de nucleones dentro del alcance
+        let is_nucleon = |p: &Particle| p.ptype == ParticleType::Proton || p.ptype == ParticleType::Neutron;
+        let grid = SpatialGrid::new(particles, self.params.nuclear_range);
+        for (i, particle_i) in particles.iter().enumerate() {
+            if !is_nucleon(particle_i) {
+                continue;
+            }
+            for j in self.nuclear_neighbors(&grid, particle_i) {
+                if j <= i || !is_nucleon(&particles[j]) {
+                    continue;
+                }
+                let (dx, dy) = self.separation(particle_i, &particles[j]);
+                let distance = dx.hypot(dy).max(1.0);
+                d.nuclear_energy += Physics::calculate_nuclear_potential(distance, &self.params);
+            }
+        }
+
+        // Potenciales de largo alcance entre partículas cargadas
+        let charged: Vec<&Particle> = particles.iter().filter(|p| p.charge != 0.0).collect();
+        for (a, particle_i) in charged.iter().enumerate() {
+            for particle_j in &charged[a + 1..] {
+                let (dx, dy) = self.separation(particle_i, particle_j);
+                let distance = dx.hypot(dy).max(1.0);
+                d.coulomb_energy +=
+                    Physics::calculate_coulomb_potential(distance, particle_i.charge, particle_j.charge, &self.params);
+
+                if particle_i.ptype == ParticleType::Proton && particle_j.ptype == ParticleType::Proton {
+                    d.repulsion_energy += Physics::calculate_repulsion_potential(distance, self.params.proton_repulsion);
+                }
+                if particle_i.ptype == ParticleType::Electron && particle_j.ptype == ParticleType::Electron {
+                    d.repulsion_energy += Physics::calculate_repulsion_potential(distance, self.params.electron_repulsion);
+                }
+            }
+        }
+
+        // Campo eléctrico externo (con contorno periódico no es conservativo y no se cuenta)
+        if self.boundary != BoundaryMode::Periodic {
+            d.field_energy = charged.iter().map(|p| Physics::calculate_field_potential(p, &self.params)).sum();
+        }
+
+        if self.params.bonding_enabled {
+            d.bond_energy = self.bond_energy();
+        }
+
+        d.temperature = self.temperature();
+
+        d.total_energy = d.kinetic_energy + d.coulomb_energy + d.nuclear_energy + d.repulsion_energy + d.bond_energy
+            + d.field_energy;
+        d
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::BoundaryMode;
+    use crate::integrator::Integrator;
+    use crate::physics::NuclearModel;
+
+    // Protones y neutrones aislados (contorno abierto, sin electrones ni procesos
+    // estocásticos): solo intervienen fuerzas por pares
+    fn isolated_system() -> Simulation {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.boundary = BoundaryMode::Open;
+        simulation.params.integrator = Integrator::VelocityVerlet;
+        simulation.params.nuclear_model = NuclearModel::Yukawa;
+        simulation.params.dt = 0.25;
+        simulation.add_particle_with_velocity(300.0, 300.0, ParticleType::Proton, 0.3, 0.1);
+        simulation.add_particle_with_velocity(380.0, 310.0, ParticleType::Proton, -0.2, 0.0);
+        simulation.add_particle_with_velocity(400.0, 200.0, ParticleType::Neutron, 0.0, -0.4);
+        simulation.add_particle_with_velocity(425.0, 205.0, ParticleType::Neutron, 0.1, 0.3);
+        simulation.add_particle_with_velocity(250.0, 420.0, ParticleType::Proton, 0.0, 0.2);
+        simulation
+    }
+
+    #[test]
+    fn momentum_is_conserved() {
+        let mut simulation = isolated_system();
+        let initial = simulation.diagnostics();
+        for _ in 0..400 {
+            simulation.update();
+            let d = simulation.diagnostics();
+            assert!((d.momentum_x - initial.momentum_x).abs() < 1e-9);
+            assert!((d.momentum_y - initial.momentum_y).abs() < 1e-9);
+            assert!((d.angular_momentum - initial.angular_momentum).abs() < 1e-6 * initial.angular_momentum.abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn energy_stays_bounded() {
+        let mut simulation = isolated_system();
+        let initial = simulation.diagnostics();
+        let scale = initial.kinetic_energy + initial.repulsion_energy.abs() + initial.nuclear_energy.abs();
+        let mut nuclear_seen = false;
+        for _ in 0..400 {
+            simulation.update();
+            let d = simulation.diagnostics();
+            nuclear_seen |= d.nuclear_energy != 0.0;
+            assert!((d.total_energy - initial.total_energy).abs() < 1e-4 * scale, "{} -> {}", initial.total_energy, d.total_energy);
+        }
+        assert!(nuclear_seen);
+    }
+
+    #[test]
+    fn total_is_the_sum_of_its_terms() {
+        let mut simulation = isolated_system();
+        simulation.params.electric_field_x = 0.01;
+        simulation.update();
+        let d = simulation.diagnostics();
+        let sum = d.kinetic_energy + d.coulomb_energy + d.nuclear_energy + d.repulsion_energy + d.bond_energy + d.field_energy;
+        assert!((d.total_energy - sum).abs() < 1e-12);
+        assert!(d.field_energy != 0.0);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::chemistry::BondKind;
+use crate::nuclear::DecayMode;
+use serde::Serialize;
+
+// Qué arrancó el electrón en una ionización
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IonizationCause {
+    Collision, // Partícula incidente con energía suficiente
+    Field,     // Campo eléctrico local intenso
+}
+
+// Sucesos discretos ocurridos durante `Simulation::update`, que el llamador
+// recoge con `Simulation::take_events`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SimulationEvent {
+    Decay {
+        mode: DecayMode,
+        parent_protons: usize,
+        parent_neutrons: usize,
+        daughter_protons: usize,
+        daughter_neutrons: usize,
+        energy: f64, // MeV
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    Fusion {
+        protons: usize, // Núcleo resultante
+        neutrons: usize,
+        energy: f64, // Q = defecto de masa liberado (MeV); negativo si la fusión absorbe energía
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    Fission {
+        protons: usize, // Núcleo blanco antes de capturar el neutrón
+        neutrons: usize,
+        heavy_fragment: (usize, usize), // (Z, N)
+        light_fragment: (usize, usize),
+        neutrons_released: usize,
+        energy: f64, // MeV
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    Ionization {
+        cause: IonizationCause,
+        protons: usize,
+        electrons: usize, // Electrones que quedan ligados
+        charge: i32,      // Carga del ion resultante
+        energy: f64,      // Energía de ionización (eV)
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    PhotonEmission {
+        protons: usize, // Átomo emisor
+        energy: f64,    // eV
+        wavelength: f64, // nm
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    PhotonAbsorption {
+        protons: usize, // Átomo excitado
+        energy: f64,
+        wavelength: f64,
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    ElectronTransfer {
+        donor: usize,    // Elemento que cede el electrón
+        acceptor: usize, // Elemento que lo recibe
+        donor_charge: i32, // Cargas de los iones resultantes
+        acceptor_charge: i32,
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+    BondFormation {
+        bond: BondKind,
+        order: usize,
+        protons: (usize, usize), // Elementos de los dos átomos
+        x: f64,                  // Punto medio del enlace
+        y: f64,
+        time_step: f64,
+    },
+    BondBreaking {
+        bond: BondKind,
+        protons: (usize, usize),
+        x: f64,
+        y: f64,
+        time_step: f64,
+    },
+}
@@ -0,0 +1,432 @@
+use crate::chemistry::BONDED_NUCLEUS_PROTONS;
+use crate::events::SimulationEvent;
+use crate::fusion::ClusterState;
+use crate::nuclear::{binding_energy, is_fissile};
+use crate::particle::{Particle, ParticleId, ParticleType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use crate::simulation::{Simulation, NUCLEUS_CLUSTER_RANGE, NUCLEUS_LAYOUT_RADIUS, PN_ORBIT_RANGE};
+
+// Núcleos a partir de este Z (torio en adelante) candidatos a fisión
+pub(crate) const HEAVY_NUCLEUS_PROTONS: usize = 90;
+
+
+// Neutrones lentos (térmicos): rapidez relativa al núcleo por debajo de este valor
+const SLOW_NEUTRON_SPEED: f64 = 3.0;
+
+// Distribución de masas asimétrica (doble joroba): el fragmento pesado se reparte
+// alrededor de A ≈ 139 y el ligero se lleva el resto
+const HEAVY_FRAGMENT_MEAN: f64 = 139.0;
+const HEAVY_FRAGMENT_WIDTH: f64 = 5.0;
+
+// Neutrones inmediatos: 2 o 3 (media ≈ 2.4), con ~2 MeV cada uno
+const THIRD_NEUTRON_PROBABILITY: f64 = 0.4;
+pub(crate) const PROMPT_NEUTRON_ENERGY: f64 = 2.0;
+
+pub(crate) struct FissionOutcome {
+    pub released: usize,         // Neutrones inmediatos
+    pub heavy: (usize, usize),   // (Z, N) del fragmento pesado
+    pub light: (usize, usize),
+    pub energy: f64,             // Q (MeV)
+}
+
+// Núcleo que se traslada en bloque: con varios protones en el anillo de `add_nucleus`,
+// la repulsión lo desharía en pocos pasos, mucho antes de que le llegue un neutrón o
+// de que forme un enlace (ver `refresh_held_nuclei`). Cada nucleón guarda su desplazamiento respecto al centro de masas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct HeldNucleus {
+    pub nucleons: Vec<(ParticleId, f64, f64)>,
+}
+
+// Núcleo que acaba de capturar un neutrón (por ids, los índices cambian al recolocar)
+struct Capture {
+    protons: Vec<ParticleId>,
+    neutrons: Vec<ParticleId>, // Incluye el neutrón capturado
+    state: ClusterState,
+}
+
+impl Simulation {
+    // Fija la forma actual de los nucleones `ids` hasta que el núcleo se parta
+    pub(crate) fn hold_nucleus(&mut self, ids: &[ParticleId]) {
+        let Some((x, y, _, _)) = self.center_of_mass(ids) else { return };
+        let nucleons = ids
+            .iter()
+            .filter_map(|&id| self.get_particle(id))
+            .map(|p| (p.id, p.x - x, p.y - y))
+            .collect();
+        self.held_nuclei.push(HeldNucleus { nucleons });
+    }
+
+    // Al empezar el paso: mientras la fisión está activa, cada núcleo pesado detectado que
+    // aún no lo esté pasa a moverse en bloque; con los enlaces activos, también los átomos
+    // de varios protones. Sin ninguna de las dos se sueltan todos y cada nucleón vuelve a
+    // moverse solo con sus fuerzas.
+    pub(crate) fn refresh_held_nuclei(&mut self) {
+        if !self.params.fission_enabled && !self.params.bonding_enabled {
+            self.held_nuclei.clear();
+            return;
+        }
+        if self.params.fission_enabled {
+            self.hold_new_nuclei(HEAVY_NUCLEUS_PROTONS);
+        }
+        if self.params.bonding_enabled {
+            self.hold_new_nuclei(BONDED_NUCLEUS_PROTONS);
+        }
+    }
+
+    // Retiene los núcleos detectados con al menos `min_protons` que no comparten nucleones
+    // con otro ya retenido (p. ej. uno retenido más un neutrón que pasa cerca)
+    fn hold_new_nuclei(&mut self, min_protons: usize) {
+        let held: HashSet<ParticleId> =
+            self.held_nuclei.iter().flat_map(|n| n.nucleons.iter().map(|&(id, _, _)| id)).collect();
+        for nucleus in self.detect_nuclei() {
+            if nucleus.protons.len() < min_protons {
+                continue;
+            }
+            let ids: Vec<ParticleId> =
+                nucleus.protons.iter().chain(&nucleus.neutrons).map(|&idx| self.particles[idx].id).collect();
+            if !ids.iter().any(|id| held.contains(id)) {
+                self.hold_nucleus(&ids);
+            }
+        }
+    }
+
+    // Los nucleones `ids` dejan de moverse con su núcleo (expulsados, fusionados, partidos...)
+    pub(crate) fn release_nucleons(&mut self, ids: &[ParticleId]) {
+        for nucleus in &mut self.held_nuclei {
+            nucleus.nucleons.retain(|(id, _, _)| !ids.contains(id));
+        }
+        self.held_nuclei.retain(|nucleus| !nucleus.nucleons.is_empty());
+    }
+
+    // Tras integrar: cada núcleo retenido vuelve a su forma alrededor de su centro de masas,
+    // que se mueve solo con las fuerzas externas (las internas se anulan por pares)
+    pub(crate) fn apply_held_nuclei(&mut self) {
+        for k in 0..self.held_nuclei.len() {
+            let ids: Vec<ParticleId> = self.held_nuclei[k].nucleons.iter().map(|&(id, _, _)| id).collect();
+            let Some((x, y, vx, vy)) = self.center_of_mass(&ids) else { continue };
+            for &(id, ox, oy) in &self.held_nuclei[k].nucleons {
+                if let Some(idx) = self.index_of(id) {
+                    let particle = &mut self.particles[idx];
+                    particle.x = x + ox;
+                    particle.y = y + oy;
+                    particle.dx = vx;
+                    particle.dy = vy;
+                    particle.orbiting_around = None;
+                }
+            }
+        }
+    }
+
+    // Posición y velocidad del centro de masas (posiciones por imagen mínima respecto al primero)
+    fn center_of_mass(&self, ids: &[ParticleId]) -> Option<(f64, f64, f64, f64)> {
+        let particles: Vec<&Particle> = ids.iter().filter_map(|&id| self.get_particle(id)).collect();
+        let first = particles.first()?;
+        let (mut mass, mut x, mut y, mut vx, mut vy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for particle in &particles {
+            let (dx, dy) = self.separation(first, particle);
+            mass += particle.mass;
+            x += particle.mass * dx;
+            y += particle.mass * dy;
+            vx += particle.mass * particle.dx;
+            vy += particle.mass * particle.dy;
+        }
+        if mass <= 0.0 {
+            return None;
+        }
+        Some((first.x + x / mass, first.y + y / mass, vx / mass, vy / mass))
+    }
+
+    // Un neutrón libre y lento que llega este paso a un núcleo fisible (U-235, Pu-239...)
+    // es capturado y el núcleo compuesto se parte en dos fragmentos más 2-3 neutrones
+    pub(crate) fn handle_fissions(&mut self) {
+        let dt = self.params.dt;
+        let mut captures = Vec::new();
+
+        for nucleus in self.detect_nuclei() {
+            if nucleus.protons.len() < HEAVY_NUCLEUS_PROTONS {
+                continue;
+            }
+            let state = self.cluster_state(&nucleus);
+
+            // Neutrón libre que en este paso ha entrado en el radio de captura p-n de alguno
+            // de los protones (antes de que las órbitas lo liguen); los neutrones propios
+            // del núcleo ya estaban dentro, así que no cuentan
+            let protons: Vec<((f64, f64), (f64, f64))> = nucleus
+                .protons
+                .iter()
+                .map(|&idx| {
+                    let p = &self.particles[idx];
+                    ((p.x, p.y), (p.x - p.dx * dt, p.y - p.dy * dt))
+                })
+                .collect();
+            let incoming = self.particles.iter().position(|p| {
+                if p.ptype != ParticleType::Neutron || p.orbiting_around.is_some() {
+                    return false;
+                }
+                let previous = (p.x - p.dx * dt, p.y - p.dy * dt);
+                let inside_now = protons.iter().any(|&(now, _)| (p.x - now.0).hypot(p.y - now.1) < PN_ORBIT_RANGE);
+                let inside_before = protons
+                    .iter()
+                    .any(|&(_, before)| (previous.0 - before.0).hypot(previous.1 - before.1) < PN_ORBIT_RANGE);
+                let slow = (p.dx - state.vx).hypot(p.dy - state.vy) < SLOW_NEUTRON_SPEED;
+                inside_now && !inside_before && slow
+            });
+            let Some(incoming) = incoming else { continue };
+
+            let mut neutrons: Vec<usize> = nucleus.neutrons.iter().copied().filter(|&idx| idx != incoming).collect();
+            if !is_fissile(nucleus.protons.len(), neutrons.len()) {
+                continue; // Sin fisión: el neutrón queda capturado por las órbitas p-n
+            }
+            neutrons.push(incoming);
+            captures.push(Capture {
+                protons: nucleus.protons.iter().map(|&idx| self.particles[idx].id).collect(),
+                neutrons: neutrons.iter().map(|&idx| self.particles[idx].id).collect(),
+                state,
+            });
+        }
+
+        for capture in captures {
+            self.split_nucleus(capture);
+        }
+    }
+
+    // Sortea cómo se parte un núcleo compuesto (Z, N): neutrones inmediatos y fragmentos
+    // con la misma relación carga/masa que el núcleo compuesto
+    pub(crate) fn sample_fission(&mut self, z: usize, n: usize) -> FissionOutcome {
+        let released = if self.rng.next_f64() < THIRD_NEUTRON_PROBABILITY { 3 } else { 2 };
+        let released = released.min(n);
+
+        let remaining = z + n - released;
+        let heavy_mass_number = (HEAVY_FRAGMENT_MEAN + HEAVY_FRAGMENT_WIDTH * self.rng.next_gaussian()).round() as usize;
+        let heavy_mass_number = heavy_mass_number.clamp(remaining.div_ceil(2), remaining - 1);
+        let heavy_z = ((z * heavy_mass_number) as f64 / remaining as f64).round() as usize;
+        let heavy = (heavy_z, heavy_mass_number - heavy_z);
+        let light = (z - heavy.0, n - released - heavy.1);
+
+        let energy = binding_energy(heavy.0, heavy.1) + binding_energy(light.0, light.1) - binding_energy(z, n);
+        FissionOutcome { released, heavy, light, energy }
+    }
+
+    fn split_nucleus(&mut self, capture: Capture) {
+        let Capture { protons, neutrons, state } = capture;
+        self.release_nucleons(&[&protons[..], &neutrons[..]].concat());
+        let z = protons.len();
+        let n = neutrons.len();
+        let FissionOutcome { released, heavy, light, energy: q } = self.sample_fission(z, n);
+        let scale = self.params.nuclear_energy_scale;
+        let energy = q.max(0.0) * scale;
+
+        let heavy_ids = ([&protons[..heavy.0], &neutrons[..heavy.1]].concat(), &protons[..heavy.0]);
+        let light_ids = (
+            [&protons[heavy.0..], &neutrons[heavy.1..heavy.1 + light.1]].concat(),
+            &protons[heavy.0..],
+        );
+        let free_ids = &neutrons[heavy.1 + light.1..];
+        let mass_of = |sim: &Simulation, ids: &[ParticleId]| -> f64 {
+            ids.iter().filter_map(|&id| sim.get_particle(id)).map(|p| p.mass).sum()
+        };
+        let heavy_mass = mass_of(self, &heavy_ids.0);
+        let light_mass = mass_of(self, &light_ids.0);
+
+        // Neutrones inmediatos en direcciones aleatorias
+        let mut neutron_momentum = (0.0, 0.0);
+        let mut neutron_velocities = Vec::new();
+        let neutron_energy = (PROMPT_NEUTRON_ENERGY * scale).min(energy / (released as f64 + 1.0));
+        for &id in free_ids {
+            let mass = self.get_particle(id).map_or(1.0, |p| p.mass);
+            let speed = (2.0 * neutron_energy / mass).sqrt();
+            let angle = self.rng.next_angle();
+            let (vx, vy) = (angle.cos() * speed, angle.sin() * speed);
+            neutron_momentum.0 += mass * vx;
+            neutron_momentum.1 += mass * vy;
+            neutron_velocities.push((id, angle, vx, vy));
+        }
+
+        // El resto, repartido entre los fragmentos en sentidos opuestos conservando el momento
+        let fragment_energy = (energy - neutron_energy * released as f64).max(0.0);
+        let reduced_mass = heavy_mass * light_mass / (heavy_mass + light_mass);
+        let momentum = (2.0 * reduced_mass * fragment_energy).sqrt();
+        let angle = self.rng.next_angle();
+        let direction = (angle.cos(), angle.sin());
+        let recoil = (
+            -neutron_momentum.0 / (heavy_mass + light_mass),
+            -neutron_momentum.1 / (heavy_mass + light_mass),
+        );
+
+        // Fragmentos separados lo bastante para que `detect_nuclei` los vea como dos núcleos
+        let offset = NUCLEUS_LAYOUT_RADIUS * 1.2 + NUCLEUS_CLUSTER_RANGE * 0.5 + 10.0;
+        for (ids, sign, mass) in [(&heavy_ids, -1.0, heavy_mass), (&light_ids, 1.0, light_mass)] {
+            let center = (state.x + sign * direction.0 * offset, state.y + sign * direction.1 * offset);
+            let velocity = (
+                state.vx + recoil.0 + sign * direction.0 * momentum / mass,
+                state.vy + recoil.1 + sign * direction.1 * momentum / mass,
+            );
+            self.place_fragment(&ids.0, center, velocity);
+        }
+
+        let reach = offset + NUCLEUS_LAYOUT_RADIUS * 1.2 + NUCLEUS_CLUSTER_RANGE + 10.0;
+        for (id, angle, vx, vy) in neutron_velocities {
+            if let Some(idx) = self.index_of(id) {
+                let particle = &mut self.particles[idx];
+                particle.x = state.x + angle.cos() * reach;
+                particle.y = state.y + angle.sin() * reach;
+                particle.dx = state.vx + vx;
+                particle.dy = state.vy + vy;
+                particle.orbiting_around = None;
+            }
+        }
+
+        // Los electrones del átomo se reparten entre los fragmentos según su carga
+        let electrons = self.electrons_bound_to(&protons);
+        let split = electrons.len().min(heavy.0);
+        let light_end = electrons.len().min(heavy.0 + light.0);
+        if let Some(&proton) = heavy_ids.1.first() {
+            self.bind_electrons(&electrons[..split], proton);
+        }
+        if let Some(&proton) = light_ids.1.first() {
+            self.bind_electrons(&electrons[split..light_end], proton);
+        }
+        for &id in &electrons[light_end..] {
+            if let Some(idx) = self.index_of(id) {
+                self.particles[idx].orbiting_around = None;
+                self.particles[idx].orbital_position = None;
+            }
+        }
+
+        self.events.push(SimulationEvent::Fission {
+            protons: z,
+            neutrons: n - 1,
+            heavy_fragment: heavy,
+            light_fragment: light,
+            neutrons_released: released,
+            energy: q,
+            x: state.x,
+            y: state.y,
+            time_step: self.time_step,
+        });
+    }
+
+    // Coloca un fragmento con la disposición de `add_nucleus` (se retiene igual que
+    // el núcleo de partida, que con Z ≈ 35-57 también se desharía solo)
+    fn place_fragment(&mut self, ids: &[ParticleId], center: (f64, f64), velocity: (f64, f64)) {
+        let (protons, neutrons): (Vec<usize>, Vec<usize>) = ids
+            .iter()
+            .filter_map(|&id| self.index_of(id))
+            .partition(|&idx| self.particles[idx].ptype == ParticleType::Proton);
+        for (indices, radius) in [(protons, NUCLEUS_LAYOUT_RADIUS), (neutrons, NUCLEUS_LAYOUT_RADIUS * 1.2)] {
+            for (i, &idx) in indices.iter().enumerate() {
+                let (x, y) = Self::ring_position(center.0, center.1, i, indices.len(), radius);
+                let particle = &mut self.particles[idx];
+                particle.x = x;
+                particle.y = y;
+                particle.dx = velocity.0;
+                particle.dy = velocity.1;
+                particle.orbiting_around = None;
+            }
+        }
+        self.hold_nucleus(ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // U-235 en reposo y un neutrón lento que llega desde la izquierda
+    fn uranium_target(neutron_speed: f64) -> Simulation {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.params.fission_enabled = true;
+        simulation.add_nucleus(500.0, 300.0, 92, 143, 0);
+        simulation.add_particle_with_velocity(395.0, 300.0, ParticleType::Neutron, neutron_speed, 0.0);
+        simulation
+    }
+
+    fn fissions(simulation: &mut Simulation, steps: usize) -> Vec<SimulationEvent> {
+        let mut events = Vec::new();
+        for _ in 0..steps {
+            simulation.update();
+            events.extend(
+                simulation.take_events().into_iter().filter(|e| matches!(e, SimulationEvent::Fission { .. })),
+            );
+        }
+        events
+    }
+
+    #[test]
+    fn heavy_nucleus_holds_together() {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.params.fission_enabled = true;
+        simulation.add_nucleus(500.0, 300.0, 92, 143, 0);
+        for _ in 0..50 {
+            simulation.update();
+        }
+        let nuclei = simulation.detect_nuclei();
+        assert_eq!(nuclei.len(), 1);
+        assert_eq!((nuclei[0].protons.len(), nuclei[0].neutrons.len()), (92, 143));
+    }
+
+    #[test]
+    fn turning_fission_off_releases_the_nucleus() {
+        let mut simulation = uranium_target(1.0);
+        simulation.update();
+        assert_eq!(simulation.held_nuclei.len(), 1);
+
+        simulation.params.fission_enabled = false;
+        simulation.update();
+        assert!(simulation.held_nuclei.is_empty());
+        // Sin retener, la repulsión entre los 92 protones del anillo lo expande
+        let ring = |simulation: &Simulation| {
+            simulation
+                .particles
+                .iter()
+                .filter(|p| p.ptype == ParticleType::Proton)
+                .map(|p| (p.x - 500.0).hypot(p.y - 300.0))
+                .fold(0.0, f64::max)
+        };
+        let before = ring(&simulation);
+        for _ in 0..5 {
+            simulation.update();
+        }
+        assert!(ring(&simulation) > before);
+    }
+
+    #[test]
+    fn slow_neutron_splits_uranium_235() {
+        let mut simulation = uranium_target(1.0);
+        let events = fissions(&mut simulation, 30);
+        assert_eq!(events.len(), 1, "{:?}", events);
+        let SimulationEvent::Fission { protons, neutrons, heavy_fragment, light_fragment, neutrons_released, energy, .. } =
+            events[0]
+        else {
+            unreachable!()
+        };
+        assert_eq!((protons, neutrons), (92, 143));
+        assert!((2..=3).contains(&neutrons_released));
+        assert_eq!(heavy_fragment.0 + light_fragment.0, 92);
+        assert_eq!(heavy_fragment.1 + light_fragment.1 + neutrons_released, 144);
+        assert!(energy > 150.0 && energy < 230.0, "Q = {} MeV", energy);
+
+        // Dos fragmentos que siguen enteros
+        let nuclei = simulation.detect_nuclei();
+        let mut charges: Vec<usize> = nuclei.iter().map(|n| n.protons.len()).collect();
+        charges.sort_unstable();
+        assert_eq!(charges, vec![light_fragment.0, heavy_fragment.0]);
+        assert_eq!(simulation.held_nuclei.len(), 2);
+    }
+
+    #[test]
+    fn fast_neutron_does_not_split_uranium() {
+        let mut simulation = uranium_target(8.0);
+        assert!(fissions(&mut simulation, 20).is_empty());
+    }
+
+    #[test]
+    fn uranium_238_is_not_fissile() {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.params.fission_enabled = true;
+        simulation.add_nucleus(500.0, 300.0, 92, 146, 0);
+        simulation.add_particle_with_velocity(395.0, 300.0, ParticleType::Neutron, 1.0, 0.0);
+        assert!(fissions(&mut simulation, 30).is_empty());
+    }
+}
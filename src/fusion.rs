@@ -0,0 +1,293 @@
+use crate::events::SimulationEvent;
+use crate::nuclear::{binding_energy, coulomb_barrier};
+use crate::particle::ParticleId;
+use crate::simulation::{Nucleus, Simulation, NUCLEUS_LAYOUT_RADIUS};
+
+// Centro de masas, velocidad y tamaño de un núcleo detectado
+pub(crate) struct ClusterState {
+    pub mass: f64,
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub extent: f64, // Distancia del centro a la superficie del nucleón más alejado
+}
+
+// Núcleo detectado al empezar el paso, por ids (los índices cambian durante el paso)
+pub(crate) struct NucleusIds {
+    pub protons: Vec<ParticleId>,
+    pub neutrons: Vec<ParticleId>,
+}
+
+impl Simulation {
+    pub(crate) fn nucleus_ids(&self) -> Vec<NucleusIds> {
+        let ids = |indices: &[usize]| indices.iter().map(|&idx| self.particles[idx].id).collect();
+        self.detect_nuclei()
+            .iter()
+            .map(|nucleus| NucleusIds { protons: ids(&nucleus.protons), neutrons: ids(&nucleus.neutrons) })
+            .collect()
+    }
+
+    // Dos núcleos que se tocan y se acercan con energía cinética relativa por encima de su
+    // barrera de Coulomb se funden en uno. La energía relativa más el defecto de masa Q pasa
+    // a movimiento interno del núcleo compuesto, que después puede evaporar nucleones o
+    // desintegrarse.
+    //
+    // Se parte de los núcleos de `previous` (detectados antes de mover las partículas):
+    // `detect_nuclei` ya agrupa dos nucleones a menos de `NUCLEUS_CLUSTER_RANGE`, así que
+    // tras el paso dos núcleos ligeros que chocan aparecerían como uno solo. Dos núcleos
+    // se tocan si sus superficies quedan a menos de `nuclear_range` o si tras el paso
+    // `detect_nuclei` ya los cuenta como un único núcleo.
+    pub(crate) fn handle_fusions(&mut self, previous: &[NucleusIds]) {
+        let indices = |ids: &[ParticleId]| -> Vec<usize> { ids.iter().filter_map(|&id| self.index_of(id)).collect() };
+        let nuclei: Vec<Nucleus> = previous
+            .iter()
+            .map(|nucleus| Nucleus { protons: indices(&nucleus.protons), neutrons: indices(&nucleus.neutrons), electrons: Vec::new() })
+            .filter(|nucleus| !nucleus.protons.is_empty())
+            .collect();
+        let states: Vec<ClusterState> = nuclei.iter().map(|nucleus| self.cluster_state(nucleus)).collect();
+
+        // Núcleo actual de cada nucleón
+        let mut current = vec![usize::MAX; self.particles.len()];
+        for (k, nucleus) in self.detect_nuclei().iter().enumerate() {
+            for &idx in nucleus.protons.iter().chain(&nucleus.neutrons) {
+                current[idx] = k;
+            }
+        }
+        let merged = |a: &Nucleus, b: &Nucleus| {
+            let clusters: std::collections::HashSet<usize> =
+                a.protons.iter().chain(&a.neutrons).map(|&idx| current[idx]).filter(|&k| k != usize::MAX).collect();
+            b.protons.iter().chain(&b.neutrons).any(|&idx| clusters.contains(&current[idx]))
+        };
+        let scale = self.params.nuclear_energy_scale;
+
+        let mut fused = vec![false; nuclei.len()];
+        let mut merges = Vec::new();
+        for a in 0..nuclei.len() {
+            if fused[a] {
+                continue;
+            }
+            for b in a + 1..nuclei.len() {
+                if fused[b] {
+                    continue;
+                }
+                let (state_a, state_b) = (&states[a], &states[b]);
+                let (rx, ry) = (state_b.x - state_a.x, state_b.y - state_a.y);
+                let touching = rx.hypot(ry) <= state_a.extent + state_b.extent + self.params.nuclear_range;
+                if !touching && !merged(&nuclei[a], &nuclei[b]) {
+                    continue;
+                }
+                let (vx, vy) = (state_b.vx - state_a.vx, state_b.vy - state_a.vy);
+                if rx * vx + ry * vy >= 0.0 {
+                    continue; // Se alejan
+                }
+
+                let reduced_mass = state_a.mass * state_b.mass / (state_a.mass + state_b.mass);
+                let kinetic = 0.5 * reduced_mass * (vx * vx + vy * vy);
+                let (za, na) = (nuclei[a].protons.len(), nuclei[a].neutrons.len());
+                let (zb, nb) = (nuclei[b].protons.len(), nuclei[b].neutrons.len());
+                if kinetic < coulomb_barrier(za, za + na, zb, zb + nb) * scale {
+                    continue;
+                }
+
+                let q = binding_energy(za + zb, na + nb) - binding_energy(za, na) - binding_energy(zb, nb);
+                let excitation = kinetic + q * scale;
+                if excitation < 0.0 {
+                    continue; // Fusión endotérmica sin energía suficiente
+                }
+
+                fused[a] = true;
+                fused[b] = true;
+                merges.push((a, b, q, excitation));
+                break;
+            }
+        }
+
+        for (a, b, q, excitation) in merges {
+            let ids = |indices: &[usize]| -> Vec<ParticleId> {
+                indices.iter().map(|&idx| self.particles[idx].id).collect()
+            };
+            let protons = [ids(&nuclei[a].protons), ids(&nuclei[b].protons)].concat();
+            let neutrons = [ids(&nuclei[a].neutrons), ids(&nuclei[b].neutrons)].concat();
+            let (state_a, state_b) = (&states[a], &states[b]);
+            let mass = state_a.mass + state_b.mass;
+            let center = ClusterState {
+                mass,
+                x: (state_a.mass * state_a.x + state_b.mass * state_b.x) / mass,
+                y: (state_a.mass * state_a.y + state_b.mass * state_b.y) / mass,
+                vx: (state_a.mass * state_a.vx + state_b.mass * state_b.vx) / mass,
+                vy: (state_a.mass * state_a.vy + state_b.mass * state_b.vy) / mass,
+                extent: 0.0,
+            };
+            self.merge_nuclei(&protons, &neutrons, &center, excitation);
+
+            self.events.push(SimulationEvent::Fusion {
+                protons: protons.len(),
+                neutrons: neutrons.len(),
+                energy: q,
+                x: center.x,
+                y: center.y,
+                time_step: self.time_step,
+            });
+        }
+    }
+
+    pub(crate) fn cluster_state(&self, nucleus: &Nucleus) -> ClusterState {
+        let nucleons: Vec<usize> = nucleus.protons.iter().chain(&nucleus.neutrons).copied().collect();
+        let mut state = ClusterState { mass: 0.0, x: 0.0, y: 0.0, vx: 0.0, vy: 0.0, extent: 0.0 };
+        let Some(&first) = nucleons.first() else { return state };
+        // Posiciones por imagen mínima respecto al primer nucleón (núcleos que cruzan un borde periódico)
+        let first = &self.particles[first];
+        for &idx in &nucleons {
+            let p = &self.particles[idx];
+            let (dx, dy) = self.separation(first, p);
+            state.mass += p.mass;
+            state.x += p.mass * dx;
+            state.y += p.mass * dy;
+            state.vx += p.mass * p.dx;
+            state.vy += p.mass * p.dy;
+        }
+        if state.mass > 0.0 {
+            state.x /= state.mass;
+            state.y /= state.mass;
+            state.vx /= state.mass;
+            state.vy /= state.mass;
+        }
+        state.x += first.x;
+        state.y += first.y;
+        for &idx in &nucleons {
+            let p = &self.particles[idx];
+            let (dx, dy) = self.boundary.minimum_image(p.x - state.x, p.y - state.y, self.canvas_width, self.canvas_height);
+            state.extent = state.extent.max(dx.hypot(dy) + p.radius);
+        }
+        state
+    }
+
+    // Recoloca los nucleones como un único núcleo (disposición de `add_nucleus`) que se
+    // mueve con el centro de masas, con `excitation` repartida en velocidades aleatorias
+    // de momento total nulo. Los electrones de ambos átomos pasan al núcleo nuevo.
+    fn merge_nuclei(&mut self, protons: &[ParticleId], neutrons: &[ParticleId], center: &ClusterState, excitation: f64) {
+        self.release_nucleons(&[protons, neutrons].concat());
+        let mut thermal: Vec<(usize, f64, f64)> = Vec::new();
+        for (ids, radius) in [(protons, NUCLEUS_LAYOUT_RADIUS), (neutrons, NUCLEUS_LAYOUT_RADIUS * 1.2)] {
+            for (i, &id) in ids.iter().enumerate() {
+                let Some(idx) = self.index_of(id) else { continue };
+                let (x, y) = Self::ring_position(center.x, center.y, i, ids.len(), radius);
+                self.particles[idx].x = x;
+                self.particles[idx].y = y;
+                let angle = self.rng.next_angle();
+                thermal.push((idx, angle.cos(), angle.sin()));
+            }
+        }
+
+        // Quitar el momento neto de las velocidades aleatorias y escalarlas a la energía pedida
+        let (mut px, mut py) = (0.0, 0.0);
+        for &(idx, ux, uy) in &thermal {
+            px += self.particles[idx].mass * ux;
+            py += self.particles[idx].mass * uy;
+        }
+        let (mean_x, mean_y) = (px / center.mass, py / center.mass);
+        let internal: f64 = thermal
+            .iter()
+            .map(|&(idx, ux, uy)| 0.5 * self.particles[idx].mass * ((ux - mean_x).powi(2) + (uy - mean_y).powi(2)))
+            .sum();
+        let factor = if internal > 0.0 { (excitation / internal).sqrt() } else { 0.0 };
+        for &(idx, ux, uy) in &thermal {
+            self.particles[idx].dx = center.vx + (ux - mean_x) * factor;
+            self.particles[idx].dy = center.vy + (uy - mean_y) * factor;
+        }
+
+        // Electrones ligados a cualquiera de los protones: todos alrededor del primero
+        let Some(&first_proton) = protons.first() else { return };
+        let electrons = self.electrons_bound_to(protons);
+        self.bind_electrons(&electrons, first_proton);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleType;
+
+    // Dos núcleos iguales lanzados uno contra otro a `speed`
+    fn collision(protons: usize, neutrons: usize, electrons: usize, speed: f64) -> Simulation {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.params.fusion_enabled = true;
+        for (x, v) in [(300.0, speed), (700.0, -speed)] {
+            let first = simulation.particles.len();
+            simulation.add_nucleus(x, 300.0, protons, neutrons, electrons);
+            for particle in &mut simulation.particles[first..] {
+                particle.dx += v;
+            }
+        }
+        simulation
+    }
+
+    // Primer suceso de fusión (protones, neutrones, Q) en `steps` pasos
+    fn first_fusion(simulation: &mut Simulation, steps: usize) -> Option<(usize, usize, f64)> {
+        for _ in 0..steps {
+            simulation.update();
+            let fusion = simulation.take_events().into_iter().find_map(|event| match event {
+                SimulationEvent::Fusion { protons, neutrons, energy, .. } => Some((protons, neutrons, energy)),
+                _ => None,
+            });
+            if fusion.is_some() {
+                return fusion;
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn deuterons_fuse_into_helium() {
+        let mut simulation = collision(1, 1, 0, 6.0);
+        let (protons, neutrons, q) = first_fusion(&mut simulation, 60).expect("d + d se funden");
+        assert_eq!((protons, neutrons), (2, 2));
+        assert!(q > 0.0);
+    }
+
+    #[test]
+    fn fast_protons_fuse() {
+        // El diprotón es endotérmico: hace falta energía cinética por encima de -Q
+        let mut simulation = collision(1, 0, 0, 10.0);
+        let (protons, neutrons, q) = first_fusion(&mut simulation, 60).expect("p + p se funden");
+        assert_eq!((protons, neutrons), (2, 0));
+        assert!(q < 0.0);
+
+        let mut slow = collision(1, 0, 0, 3.0);
+        assert!(first_fusion(&mut slow, 100).is_none());
+    }
+
+    #[test]
+    fn nothing_fuses_below_the_coulomb_barrier() {
+        // Los mismos protones que se funden a v = 10 rebotan con una barrera 100 veces más alta
+        let mut simulation = collision(1, 0, 0, 10.0);
+        simulation.params.nuclear_energy_scale = 100.0;
+        assert!(first_fusion(&mut simulation, 100).is_none());
+    }
+
+    #[test]
+    fn nothing_fuses_when_disabled() {
+        let mut simulation = collision(1, 1, 0, 6.0);
+        simulation.params.fusion_enabled = false;
+        assert!(first_fusion(&mut simulation, 60).is_none());
+    }
+
+    #[test]
+    fn electrons_move_to_the_compound_nucleus() {
+        let mut simulation = collision(1, 1, 1, 6.0);
+        first_fusion(&mut simulation, 60).expect("d + d se funden");
+
+        let electrons: Vec<&crate::particle::Particle> =
+            simulation.particles.iter().filter(|p| p.ptype == ParticleType::Electron).collect();
+        assert_eq!(electrons.len(), 2);
+        let nucleus = electrons[0].orbiting_around.expect("ligado");
+        assert!(electrons.iter().all(|e| e.orbiting_around == Some(nucleus)));
+        assert_eq!(simulation.get_particle(nucleus).map(|p| p.ptype), Some(ParticleType::Proton));
+
+        // Configuración del átomo de helio: 1s²
+        let mut shells: Vec<_> = electrons.iter().map(|e| e.orbital_position.map(|(l, n, _, _)| (n, l))).collect();
+        shells.dedup();
+        assert_eq!(shells, vec![Some((1, 's'))]);
+    }
+}
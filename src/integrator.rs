@@ -0,0 +1,193 @@
+use crate::particle::Particle;
+use crate::physics::Physics;
+use serde::{Deserialize, Serialize};
+
+// Esquema numérico usado para avanzar posiciones y velocidades un paso `dt`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Integrator {
+    // v += a·dt; x += v·dt (una evaluación de fuerzas por paso)
+    SemiImplicitEuler,
+    // Kick-drift-kick (leapfrog): simpléctico, dos evaluaciones por paso
+    VelocityVerlet,
+    // Runge-Kutta clásico de 4º orden: cuatro evaluaciones por paso
+    Rk4,
+}
+
+impl Integrator {
+    pub fn from_index(index: u32) -> Self {
+        match index {
+            0 => Integrator::SemiImplicitEuler,
+            1 => Integrator::VelocityVerlet,
+            2 => Integrator::Rk4,
+            _ => Integrator::SemiImplicitEuler,
+        }
+    }
+
+    // `forces` devuelve la fuerza total sobre cada partícula para un estado dado
+    pub fn step<F>(&self, particles: &mut [Particle], dt: f64, forces: F)
+    where
+        F: Fn(&[Particle]) -> Vec<(f64, f64)>,
+    {
+        match self {
+            Integrator::SemiImplicitEuler => {
+                let f = forces(particles);
+                for (particle, (fx, fy)) in particles.iter_mut().zip(f) {
+                    Physics::apply_force(particle, fx, fy, dt);
+                    particle.x += particle.dx * dt;
+                    particle.y += particle.dy * dt;
+                }
+            }
+
+            Integrator::VelocityVerlet => {
+                let f = forces(particles);
+                for (particle, (fx, fy)) in particles.iter_mut().zip(f) {
+                    Physics::apply_force(particle, fx, fy, dt * 0.5);
+                    particle.x += particle.dx * dt;
+                    particle.y += particle.dy * dt;
+                }
+
+                let f = forces(particles);
+                for (particle, (fx, fy)) in particles.iter_mut().zip(f) {
+                    Physics::apply_force(particle, fx, fy, dt * 0.5);
+                }
+            }
+
+            Integrator::Rk4 => {
+                let initial: Vec<Particle> = particles.to_vec();
+
+                // Derivadas (dx/dt, dy/dt, dvx/dt, dvy/dt) evaluadas en un estado intermedio
+                let derivatives = |state: &[Particle]| -> Vec<(f64, f64, f64, f64)> {
+                    forces(state)
+                        .into_iter()
+                        .zip(state)
+                        .map(|((fx, fy), p)| {
+                            if p.mass == 0.0 {
+                                (p.dx, p.dy, 0.0, 0.0) // Sin masa: velocidad constante
+                            } else {
+                                (p.dx, p.dy, fx / p.mass, fy / p.mass)
+                            }
+                        })
+                        .collect()
+                };
+
+                // Estado inicial desplazado `scale·dt` a lo largo de la derivada `k`
+                let advanced = |k: &[(f64, f64, f64, f64)], scale: f64| -> Vec<Particle> {
+                    initial
+                        .iter()
+                        .zip(k)
+                        .map(|(p, &(kx, ky, kvx, kvy))| {
+                            let mut q = p.clone();
+                            q.x += kx * dt * scale;
+                            q.y += ky * dt * scale;
+                            q.dx += kvx * dt * scale;
+                            q.dy += kvy * dt * scale;
+                            q
+                        })
+                        .collect()
+                };
+
+                let k1 = derivatives(&initial);
+                let k2 = derivatives(&advanced(&k1, 0.5));
+                let k3 = derivatives(&advanced(&k2, 0.5));
+                let k4 = derivatives(&advanced(&k3, 1.0));
+
+                for (i, particle) in particles.iter_mut().enumerate() {
+                    let combine = |a: f64, b: f64, c: f64, d: f64| (a + 2.0 * b + 2.0 * c + d) / 6.0;
+                    particle.x += dt * combine(k1[i].0, k2[i].0, k3[i].0, k4[i].0);
+                    particle.y += dt * combine(k1[i].1, k2[i].1, k3[i].1, k4[i].1);
+                    particle.dx += dt * combine(k1[i].2, k2[i].2, k3[i].2, k4[i].2);
+                    particle.dy += dt * combine(k1[i].3, k2[i].3, k3[i].3, k4[i].3);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleType;
+
+    const STRENGTH: f64 = 100.0; // Atracción k/r² entre los dos cuerpos
+
+    // Dos cuerpos de masa 1 en órbita circular de radio relativo 100 (periodo ≈ 444·dt)
+    fn two_body() -> Vec<Particle> {
+        let speed = (STRENGTH / (0.5 * 100.0)).sqrt() / 2.0;
+        let mut a = Particle::new(250.0, 300.0, ParticleType::Neutron, 1.0);
+        let mut b = Particle::new(350.0, 300.0, ParticleType::Neutron, 1.0);
+        a.dy = -speed;
+        b.dy = speed;
+        vec![a, b]
+    }
+
+    fn attraction(state: &[Particle]) -> Vec<(f64, f64)> {
+        let (dx, dy) = (state[1].x - state[0].x, state[1].y - state[0].y);
+        let r = dx.hypot(dy);
+        let f = STRENGTH / (r * r);
+        vec![(f * dx / r, f * dy / r), (-f * dx / r, -f * dy / r)]
+    }
+
+    fn energy(state: &[Particle]) -> f64 {
+        let kinetic: f64 = state.iter().map(|p| 0.5 * p.mass * (p.dx * p.dx + p.dy * p.dy)).sum();
+        kinetic - STRENGTH / state[0].distance_to(&state[1])
+    }
+
+    // Mayor desviación relativa de la energía a lo largo de unas cinco órbitas
+    fn max_energy_drift(integrator: Integrator, dt: f64) -> f64 {
+        let mut particles = two_body();
+        let initial = energy(&particles);
+        let mut drift: f64 = 0.0;
+        for _ in 0..(2200.0 / dt) as usize {
+            integrator.step(&mut particles, dt, attraction);
+            drift = drift.max(((energy(&particles) - initial) / initial).abs());
+        }
+        drift
+    }
+
+    #[test]
+    fn semi_implicit_euler_keeps_energy_bounded() {
+        assert!(max_energy_drift(Integrator::SemiImplicitEuler, 1.0) < 1e-3);
+    }
+
+    #[test]
+    fn velocity_verlet_keeps_energy_bounded() {
+        assert!(max_energy_drift(Integrator::VelocityVerlet, 1.0) < 1e-7);
+    }
+
+    #[test]
+    fn rk4_is_the_most_accurate() {
+        let rk4 = max_energy_drift(Integrator::Rk4, 1.0);
+        assert!(rk4 < 1e-8);
+        assert!(rk4 < max_energy_drift(Integrator::VelocityVerlet, 1.0));
+    }
+
+    #[test]
+    fn smaller_dt_reduces_drift() {
+        for integrator in [Integrator::SemiImplicitEuler, Integrator::VelocityVerlet, Integrator::Rk4] {
+            assert!(max_energy_drift(integrator, 0.5) < max_energy_drift(integrator, 1.0), "{:?}", integrator);
+        }
+    }
+
+    #[test]
+    fn massless_particles_ignore_forces() {
+        for integrator in [Integrator::SemiImplicitEuler, Integrator::VelocityVerlet, Integrator::Rk4] {
+            let mut photon = Particle::new(0.0, 0.0, ParticleType::Photon, 0.0);
+            photon.dx = 3.0;
+            photon.dy = -4.0;
+            let mut particles = vec![photon];
+            for _ in 0..10 {
+                integrator.step(&mut particles, 0.5, |state| vec![(1e6, 1e6); state.len()]);
+            }
+            let p = &particles[0];
+            assert_eq!((p.dx, p.dy), (3.0, -4.0), "{:?}", integrator);
+            assert!((p.x - 15.0).abs() < 1e-12 && (p.y + 20.0).abs() < 1e-12, "{:?}", integrator);
+        }
+    }
+
+    #[test]
+    fn from_index_falls_back_to_euler() {
+        assert_eq!(Integrator::from_index(1), Integrator::VelocityVerlet);
+        assert_eq!(Integrator::from_index(2), Integrator::Rk4);
+        assert_eq!(Integrator::from_index(99), Integrator::SemiImplicitEuler);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::atomic::ionization_energy;
+use crate::events::{IonizationCause, SimulationEvent};
+use crate::particle::{ParticleId, ParticleType};
+use crate::physics::Physics;
+use crate::simulation::{Nucleus, Simulation, ELECTRON_CAPTURE_RANGE};
+use std::collections::HashSet;
+
+// Ionización ya decidida (por ids: los índices cambian al recolocar)
+struct Ionization {
+    cause: IonizationCause,
+    protons: usize,
+    electron: ParticleId,        // Electrón más externo, el que se arranca
+    remaining: Vec<ParticleId>,  // Resto de electrones ligados, en orden de llenado
+    energy: f64,                 // Energía de ionización (eV)
+    center: (f64, f64),
+    reach: f64,                  // Distancia al centro a la que se suelta el electrón
+    velocity: (f64, f64),        // Velocidad del átomo
+    direction: (f64, f64),       // Sentido de salida del electrón
+    speed: f64,                  // Rapidez del electrón respecto al átomo
+    projectile: Option<(ParticleId, (f64, f64))>, // Proyectil y su velocidad tras el choque
+}
+
+impl Simulation {
+    // Carga del ion: protones menos electrones ligados (los que orbitan sus protones)
+    pub fn ion_charge(&self, nucleus: &Nucleus) -> i32 {
+        let protons: Vec<ParticleId> = nucleus.protons.iter().map(|&idx| self.particles[idx].id).collect();
+        nucleus.protons.len() as i32 - self.electrons_bound_to(&protons).len() as i32
+    }
+
+    // Un átomo pierde su electrón más externo si una partícula ajena entra en su nube
+    // electrónica con energía relativa suficiente (choque) o si la fuerza eléctrica
+    // local sobre ese electrón (cargas vecinas y campo externo), aplicada a lo largo
+    // de su radio, supera la energía de ionización
+    pub(crate) fn handle_ionizations(&mut self) {
+        let dt = self.params.dt;
+        let scale = self.params.atomic_energy_scale;
+        let mut used_projectiles = HashSet::new();
+        let mut ionizations = Vec::new();
+
+        for nucleus in self.detect_nuclei() {
+            let protons: Vec<ParticleId> = nucleus.protons.iter().map(|&idx| self.particles[idx].id).collect();
+            let mut electrons = self.electrons_bound_to(&protons);
+            if electrons.is_empty() {
+                continue;
+            }
+            // Orden de llenado; el último es el más externo
+            electrons.sort_by_key(|&id| {
+                let orbital = self.get_particle(id).and_then(|p| p.orbital_position);
+                (orbital.map_or(usize::MAX, |o| self.orbital_rank(o)), id)
+            });
+
+            let electron_indices: Vec<usize> = electrons.iter().filter_map(|&id| self.index_of(id)).collect();
+            let members: HashSet<usize> = nucleus
+                .protons
+                .iter()
+                .chain(&nucleus.neutrons)
+                .chain(&electron_indices)
+                .copied()
+                .collect();
+
+            let Some(&outermost) = electrons.last() else { continue };
+            let Some(outer) = self.get_particle(outermost) else { continue };
+            // La nube electrónica está centrada en el protón al que orbitan los electrones
+            let Some(core) = outer.orbiting_around.and_then(|id| self.index_of(id)) else { continue };
+            let core = &self.particles[core];
+            let core_previous = (core.x - core.dx * dt, core.y - core.dy * dt);
+            let cloud_offset = (outer.x - core.x, outer.y - core.y);
+            let cloud_radius = cloud_offset.0.hypot(cloud_offset.1).max(1.0);
+            let outer_direction = (cloud_offset.0 / cloud_radius, cloud_offset.1 / cloud_radius);
+            let remaining = electrons[..electrons.len() - 1].to_vec();
+
+            // Choque: partícula libre que entra en este paso en la nube electrónica (radio del
+            // electrón más externo) desde fuera del núcleo. `detect_nuclei` ya cuenta como parte
+            // del núcleo a un nucleón que se acerca, así que el átomo se evalúa sin él.
+            let hit = self.particles.iter().enumerate().find_map(|(idx, p)| {
+                if p.id == core.id || p.orbiting_around.is_some() || used_projectiles.contains(&p.id) {
+                    return None;
+                }
+                let reach = cloud_radius + p.radius;
+                let previous = (p.x - p.dx * dt - core_previous.0, p.y - p.dy * dt - core_previous.1);
+                let now = (p.x - core.x, p.y - core.y);
+                if previous.0.hypot(previous.1) < reach || segment_distance(previous, now, (0.0, 0.0)) >= reach {
+                    return None;
+                }
+
+                let mut atom = nucleus.clone();
+                atom.protons.retain(|&i| i != idx);
+                atom.neutrons.retain(|&i| i != idx);
+                let state = self.cluster_state(&atom);
+                let from_outside = (p.x - p.dx * dt - (state.x - state.vx * dt))
+                    .hypot(p.y - p.dy * dt - (state.y - state.vy * dt))
+                    > state.extent;
+                let relative = (p.dx - state.vx, p.dy - state.vy);
+                let reduced_mass = p.mass * state.mass / (p.mass + state.mass);
+                let available = 0.5 * reduced_mass * (relative.0 * relative.0 + relative.1 * relative.1);
+                let z = atom.protons.len();
+                let energy = ionization_energy(z, electrons.len());
+                (from_outside && available >= energy * scale).then_some((p.id, z, energy, state, relative, available))
+            });
+
+            if let Some((projectile, z, energy, state, relative, available)) = hit {
+                let threshold = energy * scale;
+                // La energía sobrante se reparte a medias entre el electrón y el proyectil
+                let excess = available - threshold;
+                let orbital_speed = (outer.dx - state.vx).hypot(outer.dy - state.vy);
+                let speed = orbital_speed.max((excess / self.params.electron_mass).sqrt());
+                let factor = (0.5 * excess / available).sqrt();
+                used_projectiles.insert(projectile);
+                ionizations.push(Ionization {
+                    cause: IonizationCause::Collision,
+                    protons: z,
+                    electron: outermost,
+                    remaining,
+                    energy,
+                    center: (state.x, state.y),
+                    // Fuera del radio de captura de todos los protones, para que no vuelva a ligarse
+                    reach: state.extent + ELECTRON_CAPTURE_RANGE + outer.radius,
+                    velocity: (state.vx, state.vy),
+                    direction: outer_direction,
+                    speed,
+                    projectile: Some((projectile, (state.vx + relative.0 * factor, state.vy + relative.1 * factor))),
+                });
+                continue;
+            }
+
+            // Campo: fuerza eléctrica sobre el electrón más externo, la misma que le aplica
+            // `calculate_forces` (positivo = atracción) por el resto de cargas, más el campo
+            // eléctrico externo (el magnético no realiza trabajo)
+            let mut pull = (outer.charge * self.params.electric_field_x, outer.charge * self.params.electric_field_y);
+            for (idx, p) in self.particles.iter().enumerate() {
+                if p.charge == 0.0 || members.contains(&idx) {
+                    continue;
+                }
+                let offset = self.separation(outer, p);
+                let distance = offset.0.hypot(offset.1).max(1.0);
+                let magnitude = Physics::calculate_long_range_force(
+                    distance,
+                    outer,
+                    p.charge,
+                    (p.ptype == ParticleType::Proton) as usize,
+                    (p.ptype == ParticleType::Electron) as usize,
+                    &self.params,
+                );
+                pull.0 += magnitude * offset.0 / distance;
+                pull.1 += magnitude * offset.1 / distance;
+            }
+            let strength = pull.0.hypot(pull.1);
+            let z = nucleus.protons.len();
+            let energy = ionization_energy(z, electrons.len());
+            if strength > 0.0 && strength * cloud_radius >= energy * scale {
+                // El electrón sale en el sentido de la fuerza
+                let state = self.cluster_state(&nucleus);
+                let orbital_speed = (outer.dx - state.vx).hypot(outer.dy - state.vy);
+                ionizations.push(Ionization {
+                    cause: IonizationCause::Field,
+                    protons: z,
+                    electron: outermost,
+                    remaining,
+                    energy,
+                    center: (state.x, state.y),
+                    reach: state.extent + ELECTRON_CAPTURE_RANGE + outer.radius,
+                    velocity: (state.vx, state.vy),
+                    direction: (pull.0 / strength, pull.1 / strength),
+                    speed: orbital_speed,
+                    projectile: None,
+                });
+            }
+        }
+
+        for ionization in ionizations {
+            self.ionize(ionization);
+        }
+    }
+
+    fn ionize(&mut self, ionization: Ionization) {
+        let Ionization { cause, protons, electron, remaining, energy, center, reach, velocity, direction, speed, projectile } =
+            ionization;
+        let Some(idx) = self.index_of(electron) else { return };
+        let proton = self.particles[idx].orbiting_around;
+
+        let freed = &mut self.particles[idx];
+        freed.x = center.0 + direction.0 * reach;
+        freed.y = center.1 + direction.1 * reach;
+        freed.dx = velocity.0 + direction.0 * speed;
+        freed.dy = velocity.1 + direction.1 * speed;
+        freed.orbiting_around = None;
+        freed.orbital_position = None;
+
+        if let Some((id, (dx, dy))) = projectile {
+            if let Some(p) = self.index_of(id) {
+                self.particles[p].dx = dx;
+                self.particles[p].dy = dy;
+            }
+        }
+
+        // Los electrones que quedan rehacen la ocupación de subniveles
+        if let Some(proton) = proton {
+            self.bind_electrons(&remaining, proton);
+        }
+
+        self.events.push(SimulationEvent::Ionization {
+            cause,
+            protons,
+            electrons: remaining.len(),
+            charge: protons as i32 - remaining.len() as i32,
+            energy,
+            x: center.0,
+            y: center.1,
+            time_step: self.time_step,
+        });
+    }
+}
+
+// Distancia del punto `point` al segmento `start`-`end`
+fn segment_distance(start: (f64, f64), end: (f64, f64), point: (f64, f64)) -> f64 {
+    let segment = (end.0 - start.0, end.1 - start.1);
+    let length_sq = segment.0 * segment.0 + segment.1 * segment.1;
+    let t = if length_sq > 0.0 {
+        (((point.0 - start.0) * segment.0 + (point.1 - start.1) * segment.1) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (start.0 + t * segment.0 - point.0).hypot(start.1 + t * segment.1 - point.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Átomo de hidrógeno en reposo con el protón en (540, 300)
+    fn hydrogen() -> Simulation {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.params.ionization_enabled = true;
+        simulation.add_nucleus(500.0, 300.0, 1, 0, 1);
+        simulation
+    }
+
+    fn ionizations(simulation: &mut Simulation, steps: usize) -> Vec<(IonizationCause, f64, i32)> {
+        let mut found = Vec::new();
+        for _ in 0..steps {
+            simulation.update();
+            for event in simulation.take_events() {
+                if let SimulationEvent::Ionization { cause, energy, charge, .. } = event {
+                    found.push((cause, energy, charge));
+                }
+            }
+        }
+        found
+    }
+
+    // Neutrón que cruza la nube 1s (radio 50) sin acercarse al protón lo bastante para orbitarlo
+    fn neutron_hit(speed: f64) -> Vec<(IonizationCause, f64, i32)> {
+        let mut simulation = hydrogen();
+        simulation.add_particle_with_velocity(300.0, 365.0, ParticleType::Neutron, speed, 0.0);
+        ionizations(&mut simulation, (500.0 / speed) as usize)
+    }
+
+    #[test]
+    fn collision_ionizes_above_the_threshold() {
+        // Masa reducida ≈ 1.5: hacen falta v ≈ 4.26 para los 13.6 eV del hidrógeno
+        assert!(neutron_hit(4.0).is_empty());
+        let found = neutron_hit(4.5);
+        assert_eq!(found.len(), 1);
+        let (cause, energy, charge) = found[0];
+        assert_eq!(cause, IonizationCause::Collision);
+        assert!((energy - 13.6).abs() < 0.1);
+        assert_eq!(charge, 1);
+    }
+
+    #[test]
+    fn external_field_ionizes_above_the_threshold() {
+        // E·r ≥ 13.6 con r = 50: el umbral está en E ≈ 0.27
+        let mut weak = hydrogen();
+        weak.params.electric_field_x = 0.2;
+        assert!(ionizations(&mut weak, 20).is_empty());
+
+        let mut strong = hydrogen();
+        strong.params.electric_field_x = 0.4;
+        let found = ionizations(&mut strong, 20);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, IonizationCause::Field);
+
+        // El electrón sale en contra del campo
+        let proton = strong.particles.iter().find(|p| p.ptype == ParticleType::Proton).unwrap();
+        let electron = strong.particles.iter().find(|p| p.ptype == ParticleType::Electron).unwrap();
+        assert!(electron.orbiting_around.is_none());
+        assert!(electron.x < proton.x);
+    }
+}
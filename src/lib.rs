@@ -1,20 +1,72 @@
+mod atomic;
+mod barnes_hut;
+mod boundary;
+#[cfg(feature = "wasm")]
+mod canvas;
+mod chemistry;
+mod decay;
+mod diagnostics;
+mod events;
+mod fission;
+mod fusion;
+mod integrator;
+mod ionization;
+mod nuclear;
 mod particle;
+mod photon;
 mod physics;
+mod reactor;
+mod recording;
+mod render;
+mod renderer;
+mod rng;
 mod simulation;
+mod snapshot;
+mod spatial;
+mod spectrum;
+mod svg;
+mod thermostat;
+#[cfg(feature = "wasm")]
 mod utils;
 
-pub use particle::{Particle, ParticleType};
-pub use physics::PhysicsParams;
-pub use simulation::Simulation;
+pub use atomic::{electronegativity, first_ionization_energy, ionization_energy, level_energy, photon_wavelength};
+pub use boundary::BoundaryMode;
+pub use chemistry::{Bond, BondKind, Molecule};
+pub use diagnostics::Diagnostics;
+pub use events::{IonizationCause, SimulationEvent};
+pub use integrator::Integrator;
+pub use nuclear::{binding_energy, binding_energy_per_nucleon, coulomb_barrier, decay_channel, is_fissile, DecayChannel, DecayMode};
+pub use particle::{Particle, ParticleId, ParticleType};
+pub use photon::PHOTON_SPEED;
+pub use physics::{NuclearModel, Physics, PhysicsParams};
+pub use reactor::{FuelSite, Reactor, Region, RegionKind};
+pub use recording::{Playback, Recording};
+pub use renderer::Renderer;
+pub use simulation::{Nucleus, Simulation};
+pub use snapshot::{SnapshotError, SNAPSHOT_VERSION};
+pub use spectrum::{SpectralLine, Spectrum};
+pub use svg::SvgRenderer;
+pub use thermostat::Thermostat;
 
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
 use web_sys::CanvasRenderingContext2d;
 
+#[cfg(feature = "wasm")]
+pub use canvas::CanvasRenderer;
+
+#[cfg(feature = "wasm")]
 #[wasm_bindgen]
 pub struct SimulationEngine {
     simulation: Simulation,
+    recording: Option<Recording>,
+    is_recording: bool,
+    playback: Option<Playback>,
+    spectrum_nucleus: Option<usize>, // Núcleo cuya banda espectral se dibuja
 }
 
+#[cfg(feature = "wasm")]
 #[wasm_bindgen]
 impl SimulationEngine {
     #[wasm_bindgen(constructor)]
@@ -22,39 +74,154 @@ impl SimulationEngine {
         utils::set_panic_hook();
         SimulationEngine {
             simulation: Simulation::new(canvas_width, canvas_height),
+            recording: None,
+            is_recording: false,
+            playback: None,
+            spectrum_nucleus: None,
+        }
+    }
+
+    // Escena visible: el fotograma de la reproducción si está activa, si no la simulación en vivo
+    fn scene(&self) -> &Simulation {
+        match &self.playback {
+            Some(playback) => playback.simulation(),
+            None => &self.simulation,
         }
     }
 
+    // Una escena cargada no continúa la grabación ni la reproducción anteriores
+    fn replace_simulation(&mut self, simulation: Simulation) {
+        self.simulation = simulation;
+        self.recording = None;
+        self.is_recording = false;
+        self.playback = None;
+        self.spectrum_nucleus = None;
+    }
+
     #[wasm_bindgen]
     pub fn update(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            playback.advance();
+            return;
+        }
+
         self.simulation.update();
+        if self.is_recording {
+            if let Some(recording) = &mut self.recording {
+                recording.record(&self.simulation);
+            }
+        }
     }
 
     #[wasm_bindgen]
     pub fn render(&self, context: &CanvasRenderingContext2d) {
-        self.simulation.render(context);
+        let mut renderer = CanvasRenderer::new(context);
+        self.scene().render(&mut renderer);
+        if let Some(index) = self.spectrum_nucleus {
+            self.scene().render_spectrum(&mut renderer, index);
+        }
     }
 
     #[wasm_bindgen]
-    pub fn add_particle(&mut self, x: f64, y: f64, particle_type: u32) {
-        let ptype = match particle_type {
-            0 => ParticleType::Proton,
-            1 => ParticleType::Neutron,
-            2 => ParticleType::Electron,
-            _ => ParticleType::Electron,
-        };
-        self.simulation.add_particle(x, y, ptype);
+    pub fn export_svg(&self) -> String {
+        let scene = self.scene();
+        let mut renderer = SvgRenderer::new(scene.canvas_width, scene.canvas_height);
+        scene.render(&mut renderer);
+        if let Some(index) = self.spectrum_nucleus {
+            scene.render_spectrum(&mut renderer, index);
+        }
+        renderer.finish()
     }
 
+    // Empieza una grabación nueva (fotograma clave cada `keyframe_interval` pasos)
     #[wasm_bindgen]
-    pub fn add_particle_with_velocity(&mut self, x: f64, y: f64, particle_type: u32, dx: f64, dy: f64) {
-        let ptype = match particle_type {
-            0 => ParticleType::Proton,
-            1 => ParticleType::Neutron,
-            2 => ParticleType::Electron,
-            _ => ParticleType::Electron,
-        };
-        self.simulation.add_particle_with_velocity(x, y, ptype, dx, dy);
+    pub fn start_recording(&mut self, keyframe_interval: usize) {
+        let mut recording = Recording::new(
+            self.simulation.canvas_width,
+            self.simulation.canvas_height,
+            keyframe_interval,
+        );
+        recording.record(&self.simulation);
+        self.recording = Some(recording);
+        self.is_recording = true;
+    }
+
+    #[wasm_bindgen]
+    pub fn stop_recording(&mut self) {
+        self.is_recording = false;
+    }
+
+    #[wasm_bindgen]
+    pub fn get_recorded_frame_count(&self) -> usize {
+        match (&self.playback, &self.recording) {
+            (Some(playback), _) => playback.frame_count(),
+            (None, Some(recording)) => recording.frame_count(),
+            (None, None) => 0,
+        }
+    }
+
+    // Pasa al modo reproducción; `update` deja de ejecutar la física en vivo
+    #[wasm_bindgen]
+    pub fn start_playback(&mut self) -> bool {
+        if let Some(recording) = self.recording.take() {
+            self.is_recording = false;
+            self.playback = Some(Playback::new(recording));
+            true
+        } else {
+            false
+        }
+    }
+
+    // Vuelve a la simulación en vivo conservando la grabación
+    #[wasm_bindgen]
+    pub fn stop_playback(&mut self) {
+        if let Some(playback) = self.playback.take() {
+            self.recording = Some(playback.into_recording());
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn is_playing_back(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_playback_paused(&mut self, paused: bool) {
+        if let Some(playback) = &mut self.playback {
+            playback.set_paused(paused);
+        }
+    }
+
+    // Fotogramas por tick (1 = tiempo real, 0.5 = cámara lenta, negativo = hacia atrás)
+    #[wasm_bindgen]
+    pub fn set_playback_speed(&mut self, speed: f64) {
+        if let Some(playback) = &mut self.playback {
+            playback.set_speed(speed);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn seek_frame(&mut self, frame: usize) {
+        if let Some(playback) = &mut self.playback {
+            playback.seek(frame);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn get_playback_frame(&self) -> usize {
+        self.playback.as_ref().map_or(0, |playback| playback.current_frame())
+    }
+
+    #[wasm_bindgen]
+    pub fn add_particle(&mut self, x: f64, y: f64, particle_type: u32) -> ParticleId {
+        let ptype = ParticleType::from_index(particle_type);
+        self.simulation.add_particle(x, y, ptype)
+    }
+
+    #[wasm_bindgen]
+    pub fn add_particle_with_velocity(&mut self, x: f64, y: f64, particle_type: u32, dx: f64, dy: f64) -> ParticleId {
+        let ptype = ParticleType::from_index(particle_type);
+        self.simulation.add_particle_with_velocity(x, y, ptype, dx, dy)
     }
 
     #[wasm_bindgen]
@@ -67,6 +234,32 @@ impl SimulationEngine {
         self.simulation.add_nucleus_with_charge(x, y, protons, neutrons, electrons, charge);
     }
 
+    #[wasm_bindgen]
+    pub fn save_state(&self) -> String {
+        self.simulation.to_json().unwrap_or_default()
+    }
+
+    // Reemplaza la escena actual; si el JSON no es válido la escena no cambia
+    #[wasm_bindgen]
+    pub fn load_state(&mut self, json: &str) -> Result<(), JsValue> {
+        let simulation = Simulation::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.replace_simulation(simulation);
+        Ok(())
+    }
+
+    // Snapshot binario (Uint8Array en JavaScript)
+    #[wasm_bindgen]
+    pub fn save_snapshot(&self) -> Vec<u8> {
+        self.simulation.to_snapshot()
+    }
+
+    #[wasm_bindgen]
+    pub fn load_snapshot(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let simulation = Simulation::from_snapshot(bytes).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.replace_simulation(simulation);
+        Ok(())
+    }
+
     #[wasm_bindgen]
     pub fn clear(&mut self) {
         self.simulation.clear();
@@ -87,6 +280,231 @@ impl SimulationEngine {
         self.simulation.params.nuclear_attractive = force;
     }
 
+    // 0 = rampa lineal original, 1 = Yukawa con núcleo repulsivo
+    #[wasm_bindgen]
+    pub fn set_nuclear_model(&mut self, model: u32) {
+        self.simulation.params.nuclear_model = NuclearModel::from_index(model);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_decay_enabled(&mut self, enabled: bool) {
+        self.simulation.params.decay_enabled = enabled;
+    }
+
+    // Segundos físicos por unidad de dt: valores grandes aceleran las desintegraciones lentas
+    #[wasm_bindgen]
+    pub fn set_decay_time_scale(&mut self, scale: f64) {
+        self.simulation.params.decay_time_scale = scale;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_fusion_enabled(&mut self, enabled: bool) {
+        self.simulation.params.fusion_enabled = enabled;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_fission_enabled(&mut self, enabled: bool) {
+        self.simulation.params.fission_enabled = enabled;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_ionization_enabled(&mut self, enabled: bool) {
+        self.simulation.params.ionization_enabled = enabled;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_radiation_enabled(&mut self, enabled: bool) {
+        self.simulation.params.radiation_enabled = enabled;
+    }
+
+    // Al desactivarlos se sueltan todos los enlaces existentes
+    #[wasm_bindgen]
+    pub fn set_bonding_enabled(&mut self, enabled: bool) {
+        self.simulation.params.bonding_enabled = enabled;
+        if !enabled {
+            self.simulation.bonds.clear();
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_electron_transfer_enabled(&mut self, enabled: bool) {
+        self.simulation.params.electron_transfer_enabled = enabled;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_bond_stiffness(&mut self, stiffness: f64) {
+        self.simulation.params.bond_stiffness = stiffness;
+    }
+
+    // Campo eléctrico uniforme en el plano del lienzo
+    #[wasm_bindgen]
+    pub fn set_electric_field(&mut self, x: f64, y: f64) {
+        self.simulation.params.electric_field_x = x;
+        self.simulation.params.electric_field_y = y;
+    }
+
+    // Campo magnético uniforme perpendicular al lienzo (componente z)
+    #[wasm_bindgen]
+    pub fn set_magnetic_field(&mut self, b: f64) {
+        self.simulation.params.magnetic_field = b;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_thermostat_enabled(&mut self, enabled: bool) {
+        self.simulation.params.thermostat_enabled = enabled;
+    }
+
+    // 0 = reescalado de velocidades, 1 = Berendsen, 2 = Langevin
+    #[wasm_bindgen]
+    pub fn set_thermostat(&mut self, thermostat: u32) {
+        self.simulation.params.thermostat = Thermostat::from_index(thermostat);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_target_temperature(&mut self, temperature: f64) {
+        self.simulation.params.target_temperature = temperature;
+    }
+
+    // Tiempo de relajación de Berendsen o inverso de la fricción de Langevin
+    #[wasm_bindgen]
+    pub fn set_thermostat_time(&mut self, time: f64) {
+        self.simulation.params.thermostat_time = time;
+    }
+
+    // Fotón de `energy` eV en la dirección `angle` (radianes)
+    #[wasm_bindgen]
+    pub fn add_photon(&mut self, x: f64, y: f64, angle: f64, energy: f64) -> ParticleId {
+        self.simulation.add_photon(x, y, angle, energy)
+    }
+
+    // Unidades de energía cinética de la simulación que equivalen a 1 eV
+    #[wasm_bindgen]
+    pub fn set_atomic_energy_scale(&mut self, scale: f64) {
+        self.simulation.params.atomic_energy_scale = scale;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_nuclear_energy_scale(&mut self, scale: f64) {
+        self.simulation.params.nuclear_energy_scale = scale;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_random_seed(&mut self, seed: u32) {
+        self.simulation.set_seed(seed as u64);
+    }
+
+    // Sucesos (desintegraciones, fusiones, fisiones) desde la última llamada, como JSON
+    #[wasm_bindgen]
+    pub fn take_events(&mut self) -> String {
+        serde_json::to_string(&self.simulation.take_events()).unwrap_or_default()
+    }
+
+    // Escenario de reactor: combustible, moderadores y barras de control
+    #[wasm_bindgen]
+    pub fn start_reactor(&mut self) {
+        self.simulation.reactor.get_or_insert_with(Reactor::default);
+    }
+
+    #[wasm_bindgen]
+    pub fn stop_reactor(&mut self) {
+        self.simulation.reactor = None;
+    }
+
+    #[wasm_bindgen]
+    pub fn add_fuel(&mut self, x: f64, y: f64) {
+        self.simulation.reactor.get_or_insert_with(Reactor::default).add_fuel(x, y);
+    }
+
+    // Rejilla de `cols`×`rows` barras de combustible con esquina superior izquierda en (x, y)
+    #[wasm_bindgen]
+    pub fn add_fuel_lattice(&mut self, x: f64, y: f64, cols: usize, rows: usize, spacing: f64) {
+        let reactor = self.simulation.reactor.get_or_insert_with(Reactor::default);
+        for row in 0..rows {
+            for col in 0..cols {
+                reactor.add_fuel(x + col as f64 * spacing, y + row as f64 * spacing);
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn add_moderator(&mut self, x: f64, y: f64, width: f64, height: f64) -> usize {
+        self.simulation
+            .reactor
+            .get_or_insert_with(Reactor::default)
+            .add_region(RegionKind::Moderator, x, y, width, height)
+    }
+
+    #[wasm_bindgen]
+    pub fn add_control_rod(&mut self, x: f64, y: f64, width: f64, height: f64) -> usize {
+        self.simulation
+            .reactor
+            .get_or_insert_with(Reactor::default)
+            .add_region(RegionKind::ControlRod, x, y, width, height)
+    }
+
+    // Mueve una región (p. ej. insertar o retirar una barra de control)
+    #[wasm_bindgen]
+    pub fn move_region(&mut self, index: usize, x: f64, y: f64) -> bool {
+        match self.simulation.reactor.as_mut().and_then(|reactor| reactor.regions.get_mut(index)) {
+            Some(region) => {
+                region.x = x;
+                region.y = y;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn remove_region(&mut self, index: usize) -> bool {
+        match &mut self.simulation.reactor {
+            Some(reactor) if index < reactor.regions.len() => {
+                reactor.regions.remove(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_moderation(&mut self, moderation: f64) {
+        if let Some(reactor) = &mut self.simulation.reactor {
+            reactor.moderation = moderation.clamp(0.0, 1.0);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn refuel_reactor(&mut self) {
+        if let Some(reactor) = &mut self.simulation.reactor {
+            reactor.refuel();
+        }
+    }
+
+    // Factor de multiplicación k (null hasta la primera medida), su historia y contadores
+    #[wasm_bindgen]
+    pub fn get_reactor_data(&self) -> String {
+        let simulation = self.scene();
+        let Some(reactor) = &simulation.reactor else {
+            return "null".to_string();
+        };
+        let neutrons = simulation
+            .particles
+            .iter()
+            .filter(|p| p.ptype == ParticleType::Neutron && p.orbiting_around.is_none())
+            .count();
+        format!(
+            r#"{{"k":{},"history":{},"births":{},"deaths":{},"fissions":{},"fuel":{},"remaining_fuel":{},"free_neutrons":{}}}"#,
+            reactor.multiplication.map_or("null".to_string(), |k| k.to_string()),
+            serde_json::to_string(&reactor.history).unwrap_or_default(),
+            reactor.births,
+            reactor.deaths,
+            reactor.fissions,
+            reactor.fuel.len(),
+            reactor.remaining_fuel(),
+            neutrons
+        )
+    }
+
     #[wasm_bindgen]
     pub fn set_nuclear_damping(&mut self, damping: f64) {
         self.simulation.params.nuclear_damping = damping;
@@ -142,48 +560,151 @@ impl SimulationEngine {
         self.simulation.params.electron_damping = damping;
     }
 
+    #[wasm_bindgen]
+    pub fn set_dt(&mut self, dt: f64) {
+        self.simulation.params.dt = dt;
+    }
+
+    // 0 = Euler semi-implícito, 1 = Velocity Verlet, 2 = RK4
+    #[wasm_bindgen]
+    pub fn set_integrator(&mut self, integrator: u32) {
+        self.simulation.params.integrator = Integrator::from_index(integrator);
+    }
+
+    // 0 = paredes reflectantes, 1 = periódico, 2 = absorbente, 3 = abierto
+    #[wasm_bindgen]
+    pub fn set_boundary_mode(&mut self, mode: u32) {
+        self.simulation.boundary = BoundaryMode::from_index(mode);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_barnes_hut(&mut self, enabled: bool) {
+        self.simulation.params.barnes_hut = enabled;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_barnes_hut_theta(&mut self, theta: f64) {
+        self.simulation.params.barnes_hut_theta = theta;
+    }
+
     #[wasm_bindgen]
     pub fn get_particle_count(&self) -> usize {
-        self.simulation.particles.len()
+        self.scene().particles.len()
     }
 
     #[wasm_bindgen]
     pub fn get_particle_data(&self, index: usize) -> String {
-        if let Some(particle) = self.simulation.particles.get(index) {
-            format!(
-                r#"{{"x":{},"y":{},"type":{},"radius":{}}}"#,
-                particle.x,
-                particle.y,
-                match particle.ptype {
-                    ParticleType::Proton => 0,
-                    ParticleType::Neutron => 1,
-                    ParticleType::Electron => 2,
-                },
-                particle.radius
-            )
+        if let Some(particle) = self.scene().particles.get(index) {
+            Self::particle_json(particle)
+        } else {
+            String::new()
+        }
+    }
+
+    // Igual que `get_particle_data`, pero con el id estable de la partícula
+    #[wasm_bindgen]
+    pub fn get_particle_data_by_id(&self, id: ParticleId) -> String {
+        if let Some(particle) = self.scene().get_particle(id) {
+            Self::particle_json(particle)
         } else {
             String::new()
         }
     }
 
+    #[wasm_bindgen]
+    pub fn remove_particle(&mut self, id: ParticleId) -> bool {
+        self.simulation.remove_particle(id).is_some()
+    }
+
+    fn particle_json(particle: &Particle) -> String {
+        format!(
+            r#"{{"id":{},"x":{},"y":{},"type":{},"radius":{},"orbiting":{},"energy":{}}}"#,
+            particle.id,
+            particle.x,
+            particle.y,
+            match particle.ptype {
+                ParticleType::Proton => 0,
+                ParticleType::Neutron => 1,
+                ParticleType::Electron => 2,
+                ParticleType::Positron => 3,
+                ParticleType::Photon => 4,
+            },
+            particle.radius,
+            particle
+                .orbiting_around
+                .map_or("null".to_string(), |id| id.to_string()),
+            particle.energy
+        )
+    }
+
+    // Energías, momento lineal y angular del estado visible (JSON), para graficar su deriva
+    #[wasm_bindgen]
+    pub fn get_diagnostics(&self) -> String {
+        serde_json::to_string(&self.scene().diagnostics()).unwrap_or_default()
+    }
+
+    // Temperatura cinética de las partículas libres (k_B = 1)
+    #[wasm_bindgen]
+    pub fn get_temperature(&self) -> f64 {
+        self.scene().temperature()
+    }
+
     #[wasm_bindgen]
     pub fn get_nuclei_data(&self) -> String {
-        let nuclei = self.simulation.detect_nuclei();
+        let nuclei = self.scene().detect_nuclei();
         let mut json = String::from("[");
         for (i, nucleus) in nuclei.iter().enumerate() {
             if i > 0 {
                 json.push(',');
             }
+            // Semivida en segundos y modo dominante; null si el núcleo es estable
+            let channel = nucleus.decay_channel();
+            // Carga del ion y energía (eV) para arrancarle el siguiente electrón
+            let charge = self.scene().ion_charge(nucleus);
+            let bound = (nucleus.protons.len() as i32 - charge).max(0) as usize;
             json.push_str(&format!(
-                r#"{{"protons":{},"neutrons":{},"electrons":{}}}"#,
+                r#"{{"protons":{},"neutrons":{},"electrons":{},"charge":{},"ionization_energy":{},"binding_energy":{},"binding_energy_per_nucleon":{},"half_life":{},"decay_mode":{}}}"#,
                 nucleus.protons.len(),
                 nucleus.neutrons.len(),
-                nucleus.electrons.len()
+                nucleus.electrons.len(),
+                charge,
+                ionization_energy(nucleus.protons.len(), bound),
+                nucleus.binding_energy(),
+                nucleus.binding_energy_per_nucleon(),
+                channel.map_or("null".to_string(), |c| serde_json::to_string(&c.half_life).unwrap_or_default()),
+                channel.map_or("null".to_string(), |c| serde_json::to_string(&c.mode).unwrap_or_default())
             ));
         }
         json.push(']');
         json
     }
+
+    // Moléculas detectadas: fórmula, ids de sus átomos, número de enlaces, carga y centro
+    #[wasm_bindgen]
+    pub fn get_molecules_data(&self) -> String {
+        serde_json::to_string(&self.scene().detect_molecules()).unwrap_or_default()
+    }
+
+    // Espectro de líneas del núcleo `index` (mismo orden que `get_nuclei_data`):
+    // configuración electrónica y líneas (nm, eV, intensidad relativa); null si no existe
+    #[wasm_bindgen]
+    pub fn get_spectrum_data(&self, index: usize) -> String {
+        match self.scene().detect_nuclei().get(index) {
+            Some(nucleus) => serde_json::to_string(&self.scene().spectrum(nucleus)).unwrap_or_default(),
+            None => "null".to_string(),
+        }
+    }
+
+    // Dibuja al pie del lienzo la banda espectral del núcleo `index`
+    #[wasm_bindgen]
+    pub fn show_spectrum(&mut self, index: usize) {
+        self.spectrum_nucleus = Some(index);
+    }
+
+    #[wasm_bindgen]
+    pub fn hide_spectrum(&mut self) {
+        self.spectrum_nucleus = None;
+    }
 }
 
 // Configuración de pánico para Wasm
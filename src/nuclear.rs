@@ -0,0 +1,302 @@
+use crate::simulation::Nucleus;
+use serde::Serialize;
+
+// Coeficientes de la fórmula semiempírica de masas de Weizsäcker (MeV)
+const A_VOLUME: f64 = 15.75;
+const A_SURFACE: f64 = 17.8;
+const A_COULOMB: f64 = 0.711;
+const A_ASYMMETRY: f64 = 23.7;
+const A_PAIRING: f64 = 11.18;
+
+// Energía de enlace (MeV) de un núcleo con `protons` protones y `neutrons` neutrones:
+//   B = aV·A − aS·A^(2/3) − aC·Z(Z−1)/A^(1/3) − aA·(N−Z)²/A + δ(A, Z)
+// Un nucleón aislado no está ligado (0). Para combinaciones muy inestables la
+// fórmula puede dar valores negativos, que indican que el núcleo no se sostiene.
+pub fn binding_energy(protons: usize, neutrons: usize) -> f64 {
+    let mass_number = protons + neutrons;
+    if mass_number < 2 {
+        return 0.0;
+    }
+
+    let a = mass_number as f64;
+    let z = protons as f64;
+    let n = neutrons as f64;
+
+    let volume = A_VOLUME * a;
+    let surface = A_SURFACE * a.powf(2.0 / 3.0);
+    let coulomb = A_COULOMB * z * (z - 1.0) / a.cbrt();
+    let asymmetry = A_ASYMMETRY * (n - z).powi(2) / a;
+
+    volume - surface - coulomb - asymmetry + pairing_term(protons, neutrons)
+}
+
+pub fn binding_energy_per_nucleon(protons: usize, neutrons: usize) -> f64 {
+    let mass_number = protons + neutrons;
+    if mass_number == 0 {
+        return 0.0;
+    }
+    binding_energy(protons, neutrons) / mass_number as f64
+}
+
+// Término de apareamiento: + par-par, − impar-impar, 0 con A impar
+fn pairing_term(protons: usize, neutrons: usize) -> f64 {
+    let delta = A_PAIRING / ((protons + neutrons) as f64).sqrt();
+    match (protons % 2, neutrons % 2) {
+        (0, 0) => delta,
+        (1, 1) => -delta,
+        _ => 0.0,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayMode {
+    Alpha,           // Emite 2p + 2n
+    BetaMinus,       // n → p + e⁻ (+ antineutrino)
+    BetaPlus,        // p → n + e⁺ (+ neutrino)
+    ElectronCapture, // p + e⁻ → n (+ neutrino); requiere electrones ligados
+    NeutronEmission, // Núcleo más allá de la línea de goteo de neutrones
+}
+
+// Canal de desintegración dominante de un núcleo inestable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecayChannel {
+    pub mode: DecayMode,
+    pub half_life: f64, // Segundos
+    pub energy: f64,    // Energía liberada Q (MeV)
+}
+
+// Altura de la barrera de Coulomb (MeV) entre dos núcleos en contacto:
+//   E = e²/(4πε₀) · Z1·Z2 / (r0·(A1^(1/3) + A2^(1/3))),  e²/(4πε₀) = 1.44 MeV·fm, r0 = 1.2 fm
+pub fn coulomb_barrier(protons_a: usize, mass_number_a: usize, protons_b: usize, mass_number_b: usize) -> f64 {
+    let radii = 1.2 * ((mass_number_a as f64).cbrt() + (mass_number_b as f64).cbrt());
+    if radii <= 0.0 {
+        return 0.0;
+    }
+    1.44 * (protons_a * protons_b) as f64 / radii
+}
+
+// Núcleos que fisionan al capturar un neutrón lento: pesados (Z ≥ 90) con N impar,
+// porque el neutrón capturado completa un par y aporta la energía de apareamiento
+// que falta para superar la barrera de fisión (U-235, Pu-239 sí; U-238 no)
+pub fn is_fissile(protons: usize, neutrons: usize) -> bool {
+    protons >= 90 && neutrons % 2 == 1
+}
+
+// Diferencia de masa neutrón - protón y masa del par e⁺e⁻ (MeV)
+const NEUTRON_PROTON_MASS_DIFF: f64 = 0.782;
+const ELECTRON_PAIR_MASS: f64 = 1.022;
+
+// Semivida del neutrón libre (s)
+pub const FREE_NEUTRON_HALF_LIFE: f64 = 613.9;
+
+// Estimaciones cuando el núclido no está en la tabla:
+// regla de Sargent para β (T ∝ Q⁻⁵, calibrada con el neutrón libre), captura
+// electrónica ∝ 1/(Z³·Q²) (calibrada con el Be-7) y Viola-Seaborg para α
+const BETA_HALF_LIFE_AT_1MEV: f64 = 200.0;
+const CAPTURE_HALF_LIFE_CONSTANT: f64 = 2.2e8;
+const NEUTRON_EMISSION_HALF_LIFE: f64 = 1e-21;
+
+// Núclidos conocidos (Z, N, canal); `None` = estable. Cubre los isótopos estables
+// ligeros (donde la fórmula semiempírica falla) y algunos radiactivos clásicos.
+// (modo, semivida en s, Q en MeV)
+type KnownDecay = Option<(DecayMode, f64, f64)>;
+
+const KNOWN_NUCLIDES: &[(usize, usize, KnownDecay)] = &[
+    (1, 1, None),                                          // H-2
+    (1, 2, Some((DecayMode::BetaMinus, 3.888e8, 0.0186))), // H-3 (tritio)
+    (1, 3, Some((DecayMode::NeutronEmission, 1.4e-22, 2.9))),
+    (2, 1, None),
+    (2, 2, None),
+    (2, 3, Some((DecayMode::NeutronEmission, 7.0e-22, 0.735))),
+    (2, 4, Some((DecayMode::BetaMinus, 0.807, 3.51))),
+    (3, 3, None),
+    (3, 4, None),
+    (3, 5, Some((DecayMode::BetaMinus, 0.839, 16.0))),
+    (4, 3, Some((DecayMode::ElectronCapture, 4.6e6, 0.862))), // Be-7
+    (4, 4, Some((DecayMode::Alpha, 8.2e-17, 0.092))),         // Be-8 → 2 α
+    (4, 5, None),
+    (5, 5, None),
+    (5, 6, None),
+    (6, 5, Some((DecayMode::BetaPlus, 1220.0, 0.96))),
+    (6, 6, None),
+    (6, 7, None),
+    (6, 8, Some((DecayMode::BetaMinus, 1.808e11, 0.156))), // C-14
+    (7, 6, Some((DecayMode::BetaPlus, 598.0, 1.2))),
+    (7, 7, None),
+    (7, 8, None),
+    (8, 7, Some((DecayMode::BetaPlus, 122.0, 1.73))),
+    (8, 8, None),
+    (8, 9, None),
+    (8, 10, None),
+    (9, 9, Some((DecayMode::BetaPlus, 6586.0, 0.634))), // F-18
+    (9, 10, None),
+    (10, 10, None),
+    (10, 11, None),
+    (10, 12, None),
+    (11, 11, Some((DecayMode::BetaPlus, 8.2e7, 0.546))),
+    (11, 12, None),
+    (12, 12, None),
+    (12, 13, None),
+    (12, 14, None),
+    (13, 14, None),
+    (14, 14, None),
+    (14, 15, None),
+    (14, 16, None),
+    (15, 16, None),
+    (16, 16, None),
+    (16, 17, None),
+    (16, 18, None),
+    (16, 20, None),
+    (17, 18, None),
+    (17, 20, None),
+    (18, 18, None),
+    (18, 20, None),
+    (18, 22, None),
+    (19, 20, None),
+    (19, 21, Some((DecayMode::BetaMinus, 3.94e16, 1.31))), // K-40
+    (19, 22, None),
+    (20, 20, None),
+    (20, 22, None),
+    (20, 23, None),
+    (20, 24, None),
+    (20, 26, None),
+    (20, 28, None),
+    (26, 28, None),
+    (26, 30, None), // Fe-56
+    (26, 31, None),
+    (26, 32, None),
+    (27, 32, None),
+    (27, 33, Some((DecayMode::BetaMinus, 1.663e8, 2.82))), // Co-60
+    (28, 30, None),
+    (28, 32, None),
+    (28, 34, None),
+    (29, 34, None),
+    (29, 36, None),
+    (38, 52, Some((DecayMode::BetaMinus, 9.1e8, 0.546))),  // Sr-90
+    (53, 78, Some((DecayMode::BetaMinus, 6.93e5, 0.97))),  // I-131
+    (55, 82, Some((DecayMode::BetaMinus, 9.5e8, 1.18))),   // Cs-137
+    (79, 118, None),
+    (82, 124, None),
+    (82, 125, None),
+    (82, 126, None),
+    (84, 126, Some((DecayMode::Alpha, 1.2e7, 5.41))),      // Po-210
+    (86, 136, Some((DecayMode::Alpha, 3.3e5, 5.59))),      // Rn-222
+    (88, 138, Some((DecayMode::Alpha, 5.05e10, 4.87))),    // Ra-226
+    (92, 143, Some((DecayMode::Alpha, 2.22e16, 4.68))),    // U-235
+    (92, 146, Some((DecayMode::Alpha, 1.41e17, 4.27))),    // U-238
+    (94, 145, Some((DecayMode::Alpha, 7.6e11, 5.24))),     // Pu-239
+];
+
+// Canal dominante (el de menor semivida) o `None` si el núcleo es estable.
+// `bound_electrons` indica si hay electrones disponibles para la captura electrónica.
+pub fn decay_channel(protons: usize, neutrons: usize, bound_electrons: bool) -> Option<DecayChannel> {
+    if let Some((_, _, entry)) = KNOWN_NUCLIDES.iter().find(|(z, n, _)| *z == protons && *n == neutrons) {
+        return entry.and_then(|(mode, half_life, energy)| {
+            if mode == DecayMode::ElectronCapture && !bound_electrons {
+                return None; // Sin electrones no hay captura (p. ej. Be-7 totalmente ionizado)
+            }
+            Some(DecayChannel { mode, half_life, energy })
+        });
+    }
+
+    // Un nucleón aislado: el protón es estable y el neutrón libre no forma núcleo
+    if protons + neutrons < 2 || protons == 0 {
+        return None;
+    }
+
+    let z = protons as f64;
+    let parent = binding_energy(protons, neutrons);
+    let mut candidates: Vec<DecayChannel> = Vec::new();
+
+    if neutrons >= 1 {
+        // Separación de un neutrón negativa: el neutrón sobra
+        let q = binding_energy(protons, neutrons - 1) - parent;
+        if q > 0.0 {
+            candidates.push(DecayChannel { mode: DecayMode::NeutronEmission, half_life: NEUTRON_EMISSION_HALF_LIFE, energy: q });
+        }
+
+        let q = binding_energy(protons + 1, neutrons - 1) - parent + NEUTRON_PROTON_MASS_DIFF;
+        if q > 0.0 {
+            candidates.push(DecayChannel { mode: DecayMode::BetaMinus, half_life: BETA_HALF_LIFE_AT_1MEV / q.powi(5), energy: q });
+        }
+    }
+
+    if protons >= 2 {
+        let q_capture = binding_energy(protons - 1, neutrons + 1) - parent - NEUTRON_PROTON_MASS_DIFF;
+        let q = q_capture - ELECTRON_PAIR_MASS;
+        if q > 0.0 {
+            candidates.push(DecayChannel { mode: DecayMode::BetaPlus, half_life: BETA_HALF_LIFE_AT_1MEV / q.powi(5), energy: q });
+        }
+        if bound_electrons && q_capture > 0.0 {
+            candidates.push(DecayChannel {
+                mode: DecayMode::ElectronCapture,
+                half_life: CAPTURE_HALF_LIFE_CONSTANT / (z.powi(3) * q_capture * q_capture),
+                energy: q_capture,
+            });
+        }
+    }
+
+    if protons >= 2 && neutrons >= 2 {
+        let q = binding_energy(protons - 2, neutrons - 2) + binding_energy(2, 2) - parent;
+        if q > 0.0 {
+            // Viola-Seaborg con la carga del núcleo hijo
+            let daughter_z = z - 2.0;
+            let log_half_life = (1.66175 * daughter_z - 8.5166) / q.sqrt() - 0.20228 * daughter_z - 33.9069;
+            candidates.push(DecayChannel { mode: DecayMode::Alpha, half_life: 10f64.powf(log_half_life), energy: q });
+        }
+    }
+
+    candidates.into_iter().min_by(|a, b| a.half_life.total_cmp(&b.half_life))
+}
+
+impl Nucleus {
+    pub fn mass_number(&self) -> usize {
+        self.protons.len() + self.neutrons.len()
+    }
+
+    pub fn binding_energy(&self) -> f64 {
+        binding_energy(self.protons.len(), self.neutrons.len())
+    }
+
+    pub fn binding_energy_per_nucleon(&self) -> f64 {
+        binding_energy_per_nucleon(self.protons.len(), self.neutrons.len())
+    }
+
+    // Los electrones del cúmulo cuentan como disponibles para la captura electrónica
+    pub fn decay_channel(&self) -> Option<DecayChannel> {
+        decay_channel(self.protons.len(), self.neutrons.len(), !self.electrons.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iron_56_binds_about_8_8_mev_per_nucleon() {
+        let per_nucleon = binding_energy_per_nucleon(26, 30);
+        assert!((per_nucleon - 8.8).abs() < 0.1, "{}", per_nucleon);
+    }
+
+    #[test]
+    fn binding_curve_peaks_near_iron() {
+        let fe = binding_energy_per_nucleon(26, 30);
+        assert!(binding_energy_per_nucleon(8, 8) < fe);
+        assert!(binding_energy_per_nucleon(92, 143) < fe);
+        assert!((binding_energy_per_nucleon(92, 143) - 7.6).abs() < 0.15);
+    }
+
+    #[test]
+    fn single_nucleons_are_unbound() {
+        assert_eq!(binding_energy(1, 0), 0.0);
+        assert_eq!(binding_energy(0, 1), 0.0);
+        assert_eq!(binding_energy_per_nucleon(0, 0), 0.0);
+    }
+
+    #[test]
+    fn pairing_favours_even_even_nuclei() {
+        // Mismo A = 56: el par-par Fe-56 frente al impar-impar Mn-56
+        assert!(binding_energy(26, 30) > binding_energy(25, 31));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::boundary::BoundaryMode;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -5,10 +6,30 @@ pub enum ParticleType {
     Proton,
     Neutron,
     Electron,
+    Positron, // Antipartícula del electrón (emisión β+)
+    Photon,   // Sin masa ni carga; viaja a velocidad fija con una energía (eV)
 }
 
-#[derive(Debug, Clone)]
+impl ParticleType {
+    pub fn from_index(index: u32) -> Self {
+        match index {
+            0 => ParticleType::Proton,
+            1 => ParticleType::Neutron,
+            2 => ParticleType::Electron,
+            3 => ParticleType::Positron,
+            4 => ParticleType::Photon,
+            _ => ParticleType::Electron,
+        }
+    }
+}
+
+// Identificador estable de una partícula: no cambia al eliminar otras partículas
+// y nunca se reutiliza dentro de una misma simulación
+pub type ParticleId = u32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Particle {
+    pub id: ParticleId,
     pub x: f64,
     pub y: f64,
     pub dx: f64,
@@ -17,8 +38,10 @@ pub struct Particle {
     pub radius: f64,
     pub charge: f64,
     pub mass: f64,
-    pub orbiting_around: Option<usize>,                           // Índice del protón alrededor del que orbita
+    pub orbiting_around: Option<ParticleId>,                      // Id de la partícula alrededor de la que orbita
     pub orbital_position: Option<(char, usize, usize, usize)>, // (subnivel, nivel, orbital_idx, posicion_en_orbital)
+    #[serde(default)]
+    pub energy: f64, // Energía del fotón (eV); 0 en el resto de partículas
 }
 
 impl Particle {
@@ -27,9 +50,12 @@ impl Particle {
             ParticleType::Proton => (20.0, 1.0),
             ParticleType::Neutron => (20.0, 0.0),
             ParticleType::Electron => (10.0, -1.0),
+            ParticleType::Positron => (10.0, 1.0),
+            ParticleType::Photon => (5.0, 0.0),
         };
 
         Particle {
+            id: 0, // Lo asigna `Simulation` al insertar la partícula
             x,
             y,
             dx: 0.0,
@@ -40,40 +66,55 @@ impl Particle {
             mass,
             orbiting_around: None,
             orbital_position: None,
+            energy: 0.0,
         }
     }
 
-    pub fn update(&mut self, canvas_width: f64, canvas_height: f64, damping: f64) {
-        // Actualizar posición
-        self.x += self.dx;
-        self.y += self.dy;
-
-        // Colisiones elásticas con bordes (sin pérdida de energía)
-        let reflection = match self.ptype {
-            ParticleType::Electron => -1.0, // Colisión completamente elástica
-            _ => -1.0, // Colisión completamente elástica
-        };
-
-        if self.x + self.radius > canvas_width {
-            self.x = canvas_width - self.radius;
-            self.dx *= reflection;
-        }
-        if self.x - self.radius < 0.0 {
-            self.x = self.radius;
-            self.dx *= reflection;
+    // La posición la avanza el integrador; aquí solo bordes y amortiguación
+    pub fn update(&mut self, canvas_width: f64, canvas_height: f64, boundary: BoundaryMode, damping: f64, dt: f64) {
+        // Contorno periódico: lo que sale por un borde entra por el opuesto (fotones incluidos)
+        if boundary == BoundaryMode::Periodic {
+            self.x = self.x.rem_euclid(canvas_width);
+            self.y = self.y.rem_euclid(canvas_height);
         }
-        if self.y + self.radius > canvas_height {
-            self.y = canvas_height - self.radius;
-            self.dy *= reflection;
+
+        // Los fotones ni rebotan ni se frenan: salen del lienzo (ver `Simulation::update`)
+        if self.ptype == ParticleType::Photon {
+            return;
         }
-        if self.y - self.radius < 0.0 {
-            self.y = self.radius;
-            self.dy *= reflection;
+
+        // Paredes solo en modo reflectante; en modo absorbente `Simulation::update`
+        // elimina lo que sale y en los demás las partículas cruzan el borde
+        if boundary == BoundaryMode::Reflective {
+            // Colisiones elásticas con bordes (sin pérdida de energía)
+            let reflection = match self.ptype {
+                ParticleType::Electron => -1.0, // Colisión completamente elástica
+                _ => -1.0, // Colisión completamente elástica
+            };
+
+            if self.x + self.radius > canvas_width {
+                self.x = canvas_width - self.radius;
+                self.dx *= reflection;
+            }
+            if self.x - self.radius < 0.0 {
+                self.x = self.radius;
+                self.dx *= reflection;
+            }
+            if self.y + self.radius > canvas_height {
+                self.y = canvas_height - self.radius;
+                self.dy *= reflection;
+            }
+            if self.y - self.radius < 0.0 {
+                self.y = self.radius;
+                self.dy *= reflection;
+            }
         }
 
-        // Aplicar amortiguación mínima
-        self.dx *= damping;
-        self.dy *= damping;
+        // Aplicar amortiguación mínima: `damping` es el factor por unidad de tiempo,
+        // así el frenado no depende del tamaño del paso
+        let factor = damping.powf(dt);
+        self.dx *= factor;
+        self.dy *= factor;
     }
 
     pub fn distance_to(&self, other: &Particle) -> f64 {
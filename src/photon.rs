@@ -0,0 +1,285 @@
+use crate::atomic::{angular_momentum, level_energy, photon_wavelength, transition_allowed, SUBSHELLS};
+use crate::events::SimulationEvent;
+use crate::particle::{Particle, ParticleId, ParticleType};
+use crate::simulation::Simulation;
+use std::collections::{HashMap, HashSet};
+
+// Rapidez fija de los fotones (píxeles por unidad de `dt`)
+pub const PHOTON_SPEED: f64 = 12.0;
+
+// Energía (eV) de los fotones creados sin indicarla (`add_particle`): Balmer-α, 656 nm
+pub const DEFAULT_PHOTON_ENERGY: f64 = 1.89;
+
+// Vida media de un estado excitado (unidades de `dt`) antes de emitir espontáneamente
+const EXCITED_LIFETIME: f64 = 20.0;
+
+// Tolerancia relativa de la resonancia: el fotón se absorbe si su energía difiere
+// menos de esta fracción de la diferencia entre niveles
+const ABSORPTION_TOLERANCE: f64 = 0.02;
+
+// Nivel (n, subnivel) de cada electrón ligado y protón al que orbita
+pub(crate) type ElectronLevels = HashMap<ParticleId, (ParticleId, usize, char)>;
+
+// Átomo visto desde su capa electrónica
+struct Atom {
+    protons: usize,
+    electrons: Vec<ParticleId>, // Ligados, en orden de llenado
+}
+
+impl Simulation {
+    // Fotón de `energy` eV que parte de (x, y) en la dirección `angle`
+    pub fn add_photon(&mut self, x: f64, y: f64, angle: f64, energy: f64) -> ParticleId {
+        let mut photon = Particle::new(x, y, ParticleType::Photon, 0.0);
+        photon.dx = angle.cos() * PHOTON_SPEED;
+        photon.dy = angle.sin() * PHOTON_SPEED;
+        photon.energy = energy;
+        self.push_particle(photon)
+    }
+
+    pub(crate) fn electron_levels(&self) -> ElectronLevels {
+        self.particles
+            .iter()
+            .filter(|p| p.ptype == ParticleType::Electron)
+            .filter_map(|p| {
+                let proton = p.orbiting_around?;
+                let (subshell, n, _, _) = p.orbital_position?;
+                Some((p.id, (proton, n, subshell)))
+            })
+            .collect()
+    }
+
+    fn atoms(&self) -> Vec<Atom> {
+        self.detect_nuclei()
+            .into_iter()
+            .filter_map(|nucleus| {
+                let protons: Vec<ParticleId> = nucleus.protons.iter().map(|&idx| self.particles[idx].id).collect();
+                let mut electrons = self.electrons_bound_to(&protons);
+                if electrons.is_empty() {
+                    return None;
+                }
+                electrons.sort_by_key(|&id| {
+                    let orbital = self.get_particle(id).and_then(|p| p.orbital_position);
+                    (orbital.map_or(usize::MAX, |o| self.orbital_rank(o)), id)
+                });
+                Some(Atom { protons: protons.len(), electrons })
+            })
+            .collect()
+    }
+
+    // Primer hueco libre del subnivel (n, subnivel) entre los electrones del átomo
+    fn free_slot(&self, electrons: &[ParticleId], n: usize, subshell: char) -> Option<(char, usize, usize, usize)> {
+        let occupied: HashSet<(usize, usize)> = electrons
+            .iter()
+            .filter_map(|&id| self.get_particle(id)?.orbital_position)
+            .filter(|&(s, level, _, _)| s == subshell && level == n)
+            .map(|(_, _, orbital, position)| (orbital, position))
+            .collect();
+        let orbitals = 2 * angular_momentum(subshell) + 1;
+        (0..orbitals)
+            .flat_map(|orbital| (0..2).map(move |position| (orbital, position)))
+            .find(|slot| !occupied.contains(slot))
+            .map(|(orbital, position)| (subshell, n, orbital, position))
+    }
+
+    // Absorción resonante de fotones que entran en la nube electrónica de un átomo y
+    // desexcitación espontánea de los electrones que tienen un hueco permitido debajo
+    pub(crate) fn handle_photons(&mut self) {
+        let dt = self.params.dt;
+        let decay_probability = 1.0 - (-dt / EXCITED_LIFETIME).exp();
+        let mut absorbed = HashSet::new();
+
+        for atom in self.atoms() {
+            let count = atom.electrons.len();
+            let energy_of = |n: usize, subshell: char| level_energy(atom.protons, count, n, subshell);
+
+            // Absorción: el electrón más externo sube a un subnivel permitido con hueco
+            let Some(&outermost) = atom.electrons.last() else { continue };
+            let Some(outer) = self.get_particle(outermost).cloned() else { continue };
+            let (Some((subshell, n, _, _)), Some(core)) =
+                (outer.orbital_position, outer.orbiting_around.and_then(|id| self.get_particle(id)).cloned())
+            else {
+                continue;
+            };
+            let cloud_radius = (outer.x - core.x).hypot(outer.y - core.y).max(1.0);
+            let current = energy_of(n, subshell);
+
+            let photon = self.particles.iter().find(|p| {
+                if p.ptype != ParticleType::Photon || absorbed.contains(&p.id) {
+                    return false;
+                }
+                // Solo al entrar en la nube: el fotón recién emitido por el propio átomo no cuenta
+                let previous = (p.x - p.dx * dt - (core.x - core.dx * dt), p.y - p.dy * dt - (core.y - core.dy * dt));
+                let now = (p.x - core.x, p.y - core.y);
+                previous.0.hypot(previous.1) >= cloud_radius && now.0.hypot(now.1) < cloud_radius
+            });
+            if let Some(photon) = photon.cloned() {
+                let target = SUBSHELLS.iter().copied().find(|&(level, target)| {
+                    let gap = energy_of(level, target) - current;
+                    transition_allowed(subshell, target)
+                        && gap > 0.0
+                        && (photon.energy - gap).abs() <= ABSORPTION_TOLERANCE * gap
+                        && self.free_slot(&atom.electrons, level, target).is_some()
+                });
+                if let Some((level, target)) = target {
+                    let slot = self.free_slot(&atom.electrons, level, target);
+                    if let Some(idx) = self.index_of(outermost) {
+                        self.particles[idx].orbital_position = slot;
+                    }
+                    absorbed.insert(photon.id);
+                    self.events.push(SimulationEvent::PhotonAbsorption {
+                        protons: atom.protons,
+                        energy: photon.energy,
+                        wavelength: photon_wavelength(photon.energy),
+                        x: core.x,
+                        y: core.y,
+                        time_step: self.time_step,
+                    });
+                    continue;
+                }
+            }
+
+            // Desexcitación: de fuera hacia dentro, al subnivel permitido más bajo con hueco
+            for &id in atom.electrons.iter().rev() {
+                let Some((subshell, n, _, _)) = self.get_particle(id).and_then(|p| p.orbital_position) else {
+                    continue;
+                };
+                let current = energy_of(n, subshell);
+                let target = SUBSHELLS.iter().copied().find(|&(level, target)| {
+                    transition_allowed(subshell, target)
+                        && energy_of(level, target) < current
+                        && self.free_slot(&atom.electrons, level, target).is_some()
+                });
+                let Some((level, target)) = target else { continue };
+                if self.rng.next_f64() < decay_probability {
+                    let slot = self.free_slot(&atom.electrons, level, target);
+                    if let Some(idx) = self.index_of(id) {
+                        self.particles[idx].orbital_position = slot;
+                    }
+                }
+                break; // Una transición por átomo y paso
+            }
+        }
+
+        for id in absorbed {
+            self.remove_particle(id);
+        }
+    }
+
+    // Todo electrón que este paso ha bajado de nivel sin cambiar de átomo emite un fotón
+    // con la diferencia de energía (desexcitación, reordenación tras ionizar o fusionar...)
+    pub(crate) fn emit_transition_photons(&mut self, before: &ElectronLevels) {
+        let mut emissions = Vec::new();
+        for atom in self.atoms() {
+            let count = atom.electrons.len();
+            for &id in &atom.electrons {
+                let Some(electron) = self.get_particle(id) else { continue };
+                let (Some((subshell, n, _, _)), Some(proton)) = (electron.orbital_position, electron.orbiting_around)
+                else {
+                    continue;
+                };
+                let Some(&(previous_proton, previous_n, previous_subshell)) = before.get(&id) else { continue };
+                if previous_proton != proton {
+                    continue;
+                }
+                let energy = level_energy(atom.protons, count, previous_n, previous_subshell)
+                    - level_energy(atom.protons, count, n, subshell);
+                if energy > 0.0 {
+                    emissions.push((atom.protons, electron.x, electron.y, energy));
+                }
+            }
+        }
+
+        for (protons, x, y, energy) in emissions {
+            let angle = self.rng.next_angle();
+            self.add_photon(x, y, angle, energy);
+            self.events.push(SimulationEvent::PhotonEmission {
+                protons,
+                energy,
+                wavelength: photon_wavelength(energy),
+                x,
+                y,
+                time_step: self.time_step,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hidrógeno con la radiación activa y su electrón ya en órbita 1s
+    fn hydrogen() -> (Simulation, ParticleId) {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.params.radiation_enabled = true;
+        simulation.add_nucleus(460.0, 300.0, 1, 0, 1);
+        simulation.update();
+        let electron = simulation.particles.iter().find(|p| p.ptype == ParticleType::Electron).unwrap().id;
+        (simulation, electron)
+    }
+
+    fn level(simulation: &Simulation, electron: ParticleId) -> (usize, char) {
+        let (subshell, n, _, _) = simulation.get_particle(electron).unwrap().orbital_position.unwrap();
+        (n, subshell)
+    }
+
+    #[test]
+    fn excited_hydrogen_cascades_down_emitting_photons() {
+        let (mut simulation, electron) = hydrogen();
+        let idx = simulation.index_of(electron).unwrap();
+        simulation.particles[idx].orbital_position = Some(('d', 3, 0, 0));
+        simulation.take_events();
+
+        // 3d → 2p (Balmer-α) y 2p → 1s (Lyman-α): 3d → 1s no es dipolar
+        let mut emitted = Vec::new();
+        for _ in 0..2000 {
+            simulation.update();
+            for event in simulation.take_events() {
+                if let SimulationEvent::PhotonEmission { protons: 1, energy, wavelength, .. } = event {
+                    emitted.push((energy, wavelength));
+                }
+            }
+            if level(&simulation, electron) == (1, 's') {
+                break;
+            }
+        }
+        assert_eq!(level(&simulation, electron), (1, 's'));
+        assert_eq!(emitted.len(), 2);
+        assert!((emitted[0].0 - 1.890).abs() < 0.001 && (emitted[0].1 - 656.3).abs() < 0.5);
+        assert!((emitted[1].0 - 10.204).abs() < 0.001 && (emitted[1].1 - 121.5).abs() < 0.5);
+
+        // Cada suceso deja un fotón con esa energía en la escena
+        let mut photons: Vec<f64> =
+            simulation.particles.iter().filter(|p| p.ptype == ParticleType::Photon).map(|p| p.energy).collect();
+        photons.sort_by(f64::total_cmp);
+        assert_eq!(photons, [emitted[0].0, emitted[1].0]);
+    }
+
+    // Fotón que llega desde la izquierda hacia el protón; nivel del electrón al cruzar el átomo
+    fn shine(energy: f64) -> ((usize, char), bool) {
+        let (mut simulation, electron) = hydrogen();
+        let proton = simulation.particles.iter().find(|p| p.ptype == ParticleType::Proton).unwrap().clone();
+        let photon = simulation.add_photon(proton.x - 200.0, proton.y, 0.0, energy);
+        for _ in 0..25 {
+            simulation.update();
+            let absorbed = simulation.take_events().iter().any(|e| matches!(e, SimulationEvent::PhotonAbsorption { .. }));
+            if absorbed {
+                assert!(simulation.get_particle(photon).is_none());
+                return (level(&simulation, electron), true);
+            }
+        }
+        (level(&simulation, electron), false)
+    }
+
+    #[test]
+    fn resonant_photon_excites_hydrogen() {
+        // Lyman-α: 1s → 2p
+        assert_eq!(shine(10.2), ((2, 'p'), true));
+    }
+
+    #[test]
+    fn off_resonance_photon_passes_through() {
+        assert_eq!(shine(9.0), ((1, 's'), false));
+        assert_eq!(shine(DEFAULT_PHOTON_ENERGY), ((1, 's'), false));
+    }
+}
@@ -1,9 +1,40 @@
-#[derive(Debug, Clone)]
+use crate::integrator::Integrator;
+use crate::thermostat::Thermostat;
+use serde::{Deserialize, Serialize};
+
+// Forma de la interacción nuclear entre nucleones
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NuclearModel {
+    // Rampa atractiva hasta nuclear_range/2 (modelo original, sin núcleo repulsivo)
+    Linear,
+    // Atracción de Yukawa con núcleo repulsivo de menor alcance (estilo σ-ω):
+    //   U(r) = λ·[(A + F)·e^(-2r/λ) − A·e^(-r/λ)] / (r/λ),  λ = nuclear_range/2
+    // con A = nuclear_attractive (profundidad del pozo) y F = nuclear_force (exceso
+    // del núcleo repulsivo sobre la atracción). El mínimo queda en r0 = λ·x0 con
+    // e^(-x0)·(2x0 + 1)/(x0 + 1) = A/(A + F); con los valores por defecto r0 ≈ nuclear_range/2.
+    // Se trunca en nuclear_range desplazando fuerza y potencial para que ambos sean continuos;
+    // el desplazamiento aleja algo el equilibrio (≈ 0.58·nuclear_range por defecto).
+    Yukawa,
+}
+
+impl NuclearModel {
+    pub fn from_index(index: u32) -> Self {
+        match index {
+            0 => NuclearModel::Linear,
+            1 => NuclearModel::Yukawa,
+            _ => NuclearModel::Linear,
+        }
+    }
+}
+
+// `serde(default)`: los estados guardados antes de añadir un parámetro siguen cargando
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PhysicsParams {
-    pub nuclear_force: f64,
+    pub nuclear_force: f64,       // Núcleo repulsivo; solo lo usa `NuclearModel::Yukawa`
     pub nuclear_range: f64,
     pub nuclear_attractive: f64,
-    pub nuclear_damping: f64,
+    pub nuclear_damping: f64,     // Factor de velocidad por unidad de tiempo (1 = sin pérdida)
     pub proton_repulsion: f64,
     pub coulomb_force: f64,
     pub orbital_speed: f64,
@@ -13,8 +44,31 @@ pub struct PhysicsParams {
     pub electron_repulsion: f64,
     pub nuclear_mass: f64,
     pub electron_mass: f64,
-    pub electron_damping: f64,
+    pub electron_damping: f64,    // Ídem para los electrones
     pub energy_level: u32,
+    pub dt: f64,                  // Paso de integración
+    pub integrator: Integrator,
+    pub barnes_hut: bool,         // Aproximar el largo alcance con un quadtree
+    pub barnes_hut_theta: f64,    // Ángulo de apertura (tamaño de celda / distancia)
+    pub nuclear_model: NuclearModel, // `Linear` por defecto, para no alterar escenas existentes
+    pub decay_enabled: bool,      // Desintegración radiactiva de núcleos inestables
+    pub decay_time_scale: f64,    // Segundos físicos que representa cada unidad de `dt`
+    pub nuclear_energy_scale: f64, // Unidades de energía cinética de la simulación por MeV
+    pub fusion_enabled: bool,     // Núcleos que superan la barrera de Coulomb se funden
+    pub fission_enabled: bool,    // Núcleos fisibles capturan neutrones lentos y se parten
+    pub ionization_enabled: bool, // Choques y campos intensos arrancan electrones ligados
+    pub atomic_energy_scale: f64, // Unidades de energía cinética de la simulación por eV
+    pub radiation_enabled: bool,  // Emisión y absorción de fotones en las transiciones electrónicas
+    pub bonding_enabled: bool,    // Enlaces covalentes e iónicos entre átomos cercanos
+    pub electron_transfer_enabled: bool, // El átomo más electronegativo arranca el electrón externo del otro
+    pub bond_stiffness: f64,      // Constante del muelle de enlace (por orden de enlace)
+    pub electric_field_x: f64,    // Campo eléctrico externo uniforme (componentes en el plano)
+    pub electric_field_y: f64,
+    pub magnetic_field: f64,      // Campo magnético externo uniforme, perpendicular al lienzo (componente z)
+    pub thermostat_enabled: bool, // Llevar la temperatura de las partículas libres a `target_temperature`
+    pub thermostat: Thermostat,
+    pub target_temperature: f64,  // Unidades de energía cinética de la simulación (k_B = 1)
+    pub thermostat_time: f64,     // Tiempo de relajación (Berendsen) o inverso de la fricción (Langevin)
 }
 
 impl Default for PhysicsParams {
@@ -35,6 +89,29 @@ impl Default for PhysicsParams {
             electron_mass: 0.01,
             electron_damping: 1.0,      // SIN pérdida - sistema completamente conservativo
             energy_level: 1,
+            dt: 1.0,
+            integrator: Integrator::SemiImplicitEuler,
+            barnes_hut: false,
+            barnes_hut_theta: 0.5,
+            nuclear_model: NuclearModel::Linear,
+            decay_enabled: false,
+            decay_time_scale: 1.0,
+            nuclear_energy_scale: 1.0,
+            fusion_enabled: false,
+            fission_enabled: false,
+            ionization_enabled: false,
+            atomic_energy_scale: 1.0,
+            radiation_enabled: false,
+            bonding_enabled: false,
+            electron_transfer_enabled: false,
+            bond_stiffness: 0.05,
+            electric_field_x: 0.0,
+            electric_field_y: 0.0,
+            magnetic_field: 0.0,
+            thermostat_enabled: false,
+            thermostat: Thermostat::Berendsen,
+            target_temperature: 1.0,
+            thermostat_time: 20.0,
         }
     }
 }
@@ -50,14 +127,35 @@ impl Physics {
             return 0.0;
         }
 
-        // Fuerza atractiva fuerte a corta distancia
-        let attractive = if distance < params.nuclear_range * 0.5 {
-            params.nuclear_attractive * (1.0 - distance / params.nuclear_range)
-        } else {
-            0.0
-        };
+        match params.nuclear_model {
+            NuclearModel::Linear => {
+                // Fuerza atractiva fuerte a corta distancia
+                if distance < params.nuclear_range * 0.5 {
+                    params.nuclear_attractive * (1.0 - distance / params.nuclear_range)
+                } else {
+                    0.0
+                }
+            }
+            NuclearModel::Yukawa => {
+                Self::yukawa_force(distance, params) - Self::yukawa_force(params.nuclear_range, params)
+            }
+        }
+    }
+
+    // Potencial y fuerza (dU/dr, positivo = atracción) de `NuclearModel::Yukawa` sin truncar
+    fn yukawa_potential(distance: f64, params: &PhysicsParams) -> f64 {
+        let lambda = params.nuclear_range * 0.5;
+        let x = distance / lambda;
+        let core = params.nuclear_attractive + params.nuclear_force;
+        lambda * (core * (-2.0 * x).exp() - params.nuclear_attractive * (-x).exp()) / x
+    }
 
-        attractive
+    fn yukawa_force(distance: f64, params: &PhysicsParams) -> f64 {
+        let lambda = params.nuclear_range * 0.5;
+        let x = distance / lambda;
+        let core = params.nuclear_attractive + params.nuclear_force;
+        -core * (-2.0 * x).exp() * (2.0 * x + 1.0) / (x * x)
+            + params.nuclear_attractive * (-x).exp() * (x + 1.0) / (x * x)
     }
 
     pub fn calculate_coulomb_force(
@@ -71,8 +169,99 @@ impl Physics {
         }
 
         let k = 8.9875e9;
-        let coulomb = (k * charge1 * charge2) / (distance * distance) * params.coulomb_force * 1e-15;
-        coulomb
+        (k * charge1 * charge2) / (distance * distance) * params.coulomb_force * 1e-15
+    }
+
+    // Potenciales coherentes con las fuerzas anteriores tal y como se aplican en
+    // `calculate_forces` (magnitud positiva = atracción, es decir dU/dr = magnitud)
+
+    pub fn calculate_nuclear_potential(distance: f64, params: &PhysicsParams) -> f64 {
+        match params.nuclear_model {
+            NuclearModel::Linear => {
+                // U(r) = -∫_r^{R/2} nuclear_attractive·(1 - r'/R) dr', nulo a partir de R/2
+                let cutoff = params.nuclear_range * 0.5;
+                if distance >= cutoff {
+                    return 0.0;
+                }
+                let integral = (cutoff - distance) - (cutoff * cutoff - distance * distance) / (2.0 * params.nuclear_range);
+                -params.nuclear_attractive * integral
+            }
+            NuclearModel::Yukawa => {
+                // Potencial desplazado: U(R) = 0 y dU/dr coincide con la fuerza truncada
+                let range = params.nuclear_range;
+                if distance >= range {
+                    return 0.0;
+                }
+                Self::yukawa_potential(distance, params)
+                    - Self::yukawa_potential(range, params)
+                    - (distance - range) * Self::yukawa_force(range, params)
+            }
+        }
+    }
+
+    pub fn calculate_coulomb_potential(
+        distance: f64,
+        charge1: f64,
+        charge2: f64,
+        params: &PhysicsParams,
+    ) -> f64 {
+        if distance < 1.0 {
+            return 0.0;
+        }
+
+        let k = 8.9875e9;
+        -(k * charge1 * charge2) / distance * params.coulomb_force * 1e-15
+    }
+
+    // Repulsiones p-p / e-e de la forma strength/(r + 0.1): potencial logarítmico
+    // (no tiende a cero en el infinito; la referencia es r + 0.1 = 1)
+    pub fn calculate_repulsion_potential(distance: f64, strength: f64) -> f64 {
+        -strength * (distance + 0.1).ln()
+    }
+
+    // Fuerzas de largo alcance (Coulomb + repulsiones p-p y e-e) que ejerce sobre
+    // `particle` un grupo con carga total `charge` formado por `protons` protones y
+    // `electrons` electrones, concentrado a `distance`. Positivo = atracción.
+    pub fn calculate_long_range_force(
+        distance: f64,
+        particle: &super::particle::Particle,
+        charge: f64,
+        protons: usize,
+        electrons: usize,
+        params: &PhysicsParams,
+    ) -> f64 {
+        let mut force_magnitude = 0.0;
+
+        // Repulsión entre protones
+        if particle.ptype == super::particle::ParticleType::Proton {
+            force_magnitude -= protons as f64 * params.proton_repulsion / (distance + 0.1);
+        }
+
+        // Fuerzas de Coulomb (electrostáticas)
+        force_magnitude += Self::calculate_coulomb_force(distance, particle.charge, charge, params);
+
+        // Repulsión entre electrones
+        if particle.ptype == super::particle::ParticleType::Electron {
+            force_magnitude -= electrons as f64 * params.electron_repulsion / (distance + 0.1);
+        }
+
+        force_magnitude
+    }
+
+    // Fuerza de Lorentz q·(E + v×B) de los campos externos, como vector (no como magnitud
+    // atractiva). Con B = (0, 0, Bz): v×B = (vy·Bz, −vx·Bz)
+    pub fn calculate_lorentz_force(particle: &super::particle::Particle, params: &PhysicsParams) -> (f64, f64) {
+        let q = particle.charge;
+        (
+            q * (params.electric_field_x + particle.dy * params.magnetic_field),
+            q * (params.electric_field_y - particle.dx * params.magnetic_field),
+        )
+    }
+
+    // Energía potencial de una carga en el campo eléctrico uniforme: U = −q·E·r
+    // (el campo magnético no realiza trabajo)
+    pub fn calculate_field_potential(particle: &super::particle::Particle, params: &PhysicsParams) -> f64 {
+        -particle.charge * (params.electric_field_x * particle.x + params.electric_field_y * particle.y)
     }
 
     pub fn calculate_orbital_motion(
@@ -91,11 +280,106 @@ impl Physics {
         particle: &mut super::particle::Particle,
         fx: f64,
         fy: f64,
+        dt: f64,
     ) {
+        // Sin masa (fotones): ninguna fuerza cambia su velocidad
+        if particle.mass == 0.0 {
+            return;
+        }
         let acceleration_x = fx / particle.mass;
         let acceleration_y = fy / particle.mass;
-        
-        particle.dx += acceleration_x;
-        particle.dy += acceleration_y;
+
+        particle.dx += acceleration_x * dt;
+        particle.dy += acceleration_y * dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yukawa() -> PhysicsParams {
+        PhysicsParams { nuclear_model: NuclearModel::Yukawa, ..PhysicsParams::default() }
+    }
+
+    // dU/dr por diferencias centradas
+    fn potential_slope(distance: f64, params: &PhysicsParams) -> f64 {
+        let h = 1e-5;
+        (Physics::calculate_nuclear_potential(distance + h, params) - Physics::calculate_nuclear_potential(distance - h, params)) / (2.0 * h)
+    }
+
+    // Cero de `f` en [low, high] por bisección (f(low) < 0 < f(high))
+    fn root(f: impl Fn(f64) -> f64, mut low: f64, mut high: f64) -> f64 {
+        for _ in 0..80 {
+            let mid = 0.5 * (low + high);
+            if f(mid) < 0.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        0.5 * (low + high)
+    }
+
+    #[test]
+    fn yukawa_equilibrium_matches_the_documented_condition() {
+        let params = yukawa();
+        let lambda = params.nuclear_range / 2.0;
+        let ratio = params.nuclear_attractive / (params.nuclear_attractive + params.nuclear_force);
+
+        // e^(-x0)·(2x0 + 1)/(x0 + 1) = A/(A + F) decrece con x
+        let x0 = root(|x| ratio - (-x).exp() * (2.0 * x + 1.0) / (x + 1.0), 0.1, 5.0);
+        assert!(Physics::yukawa_force(lambda * x0, &params).abs() < 1e-9);
+        assert!((lambda * x0 - params.nuclear_range / 2.0).abs() < 1.0, "r0 = {}", lambda * x0);
+    }
+
+    #[test]
+    fn truncated_yukawa_has_a_single_well() {
+        let params = yukawa();
+        // Repulsivo al solapar, atractivo a media distancia
+        assert!(Physics::calculate_nuclear_force(5.0, &params) < 0.0);
+        assert!(Physics::calculate_nuclear_force(30.0, &params) > 0.0);
+
+        let r0 = root(|r| Physics::calculate_nuclear_force(r, &params), 5.0, 30.0);
+        assert!((r0 / params.nuclear_range - 0.58).abs() < 0.01, "r0 = {}", r0);
+        let depth = Physics::calculate_nuclear_potential(r0, &params);
+        assert!(depth < 0.0);
+        assert!(depth < Physics::calculate_nuclear_potential(r0 - 3.0, &params));
+        assert!(depth < Physics::calculate_nuclear_potential(r0 + 3.0, &params));
+    }
+
+    #[test]
+    fn force_is_the_potential_slope() {
+        // Convención de `calculate_forces`: magnitud positiva = atracción, es decir F = dU/dr
+        for params in [yukawa(), PhysicsParams::default()] {
+            for distance in [3.0, 8.0, 12.5, 17.0, 19.0, 25.0, 33.0, 39.0] {
+                let force = Physics::calculate_nuclear_force(distance, &params);
+                let slope = potential_slope(distance, &params);
+                assert!((force - slope).abs() < 1e-4 * force.abs().max(1.0), "{:?} r = {}: {} vs {}", params.nuclear_model, distance, force, slope);
+            }
+        }
+    }
+
+    #[test]
+    fn yukawa_is_continuous_at_the_truncation_point() {
+        let params = yukawa();
+        let range = params.nuclear_range;
+        for distance in [range - 1e-6, range, range + 1e-6] {
+            assert!(Physics::calculate_nuclear_force(distance, &params).abs() < 1e-6);
+            assert!(Physics::calculate_nuclear_potential(distance, &params).abs() < 1e-9);
+        }
+        // Justo antes del corte la fuerza sigue coincidiendo con la pendiente del potencial
+        let slope = potential_slope(range - 0.01, &params);
+        assert!((Physics::calculate_nuclear_force(range - 0.01, &params) - slope).abs() < 1e-6);
+    }
+
+    #[test]
+    fn repulsion_potential_matches_its_force() {
+        // Fuerza −strength/(r + 0.1) (repulsiva) = dU/dr
+        let (distance, strength, h) = (37.0, 15.0, 1e-5);
+        let slope = (Physics::calculate_repulsion_potential(distance + h, strength)
+            - Physics::calculate_repulsion_potential(distance - h, strength))
+            / (2.0 * h);
+        assert!((slope + strength / (distance + 0.1)).abs() < 1e-6);
     }
 }
@@ -0,0 +1,353 @@
+use crate::boundary::BoundaryMode;
+use crate::events::SimulationEvent;
+use crate::fission::FissionOutcome;
+use crate::particle::{Particle, ParticleId, ParticleType};
+use crate::simulation::Simulation;
+use serde::{Deserialize, Serialize};
+
+// Combustible: U-235 (el núcleo compuesto tras la captura es U-236)
+const FUEL_PROTONS: usize = 92;
+const FUEL_NEUTRONS: usize = 143;
+
+// Radio de captura de una barra de combustible
+pub(crate) const FUEL_RADIUS: f64 = 24.0;
+
+// Fracción de capturas en el combustible que no acaban en fisión (captura radiativa)
+const PARASITIC_CAPTURE_PROBABILITY: f64 = 0.15;
+
+// Pasos por ventana al medir k y número de medidas que se conservan
+const MEASUREMENT_WINDOW: u32 = 50;
+const HISTORY_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RegionKind {
+    Moderator,  // Frena los neutrones hacia la velocidad térmica
+    ControlRod, // Absorbe todos los neutrones que entran
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub kind: RegionKind,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Region {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+    }
+}
+
+// Barra de combustible: un núcleo fisible representado de forma compacta (un núcleo
+// de 235 partículas reales no aguanta entero mucho tiempo ni cabe varias veces en escena)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuelSite {
+    pub x: f64,
+    pub y: f64,
+    pub spent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reactor {
+    pub fuel: Vec<FuelSite>,
+    pub regions: Vec<Region>,
+    pub moderation: f64,    // Fracción del exceso de velocidad que quita el moderador por paso
+    pub thermal_speed: f64, // Rapidez a la que el moderador deja los neutrones
+    pub fast_speed: f64,    // Rapidez de los neutrones inmediatos de fisión
+    pub births: u64,        // Neutrones nacidos por fisión (total)
+    pub deaths: u64,        // Neutrones absorbidos o perdidos (total)
+    pub fissions: u64,
+    window_steps: u32,
+    window_births: u64,
+    window_deaths: u64,
+    #[serde(default)]
+    free_neutrons: Vec<ParticleId>, // Neutrones libres en juego al terminar el paso anterior
+    pub multiplication: Option<f64>, // Última k medida (None hasta cerrar una ventana con pérdidas)
+    pub history: Vec<(f64, f64)>,    // (time_step, k)
+}
+
+impl Default for Reactor {
+    fn default() -> Self {
+        Reactor {
+            fuel: Vec::new(),
+            regions: Vec::new(),
+            moderation: 0.2,
+            thermal_speed: 1.5,
+            fast_speed: 8.0,
+            births: 0,
+            deaths: 0,
+            fissions: 0,
+            window_steps: 0,
+            window_births: 0,
+            window_deaths: 0,
+            free_neutrons: Vec::new(),
+            multiplication: None,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl Reactor {
+    pub fn add_fuel(&mut self, x: f64, y: f64) {
+        self.fuel.push(FuelSite { x, y, spent: false });
+    }
+
+    pub fn add_region(&mut self, kind: RegionKind, x: f64, y: f64, width: f64, height: f64) -> usize {
+        self.regions.push(Region { kind, x, y, width, height });
+        self.regions.len() - 1
+    }
+
+    pub fn remaining_fuel(&self) -> usize {
+        self.fuel.iter().filter(|site| !site.spent).count()
+    }
+
+    // Repone todo el combustible y reinicia las medidas
+    pub fn refuel(&mut self) {
+        for site in &mut self.fuel {
+            site.spent = false;
+        }
+        let Reactor { fuel, regions, moderation, thermal_speed, fast_speed, .. } = std::mem::take(self);
+        *self = Reactor { fuel, regions, moderation, thermal_speed, fast_speed, ..Reactor::default() };
+    }
+
+    fn region_at(&self, x: f64, y: f64) -> Option<RegionKind> {
+        // Las barras de control mandan sobre el moderador si se solapan
+        let mut found = None;
+        for region in self.regions.iter().filter(|region| region.contains(x, y)) {
+            if region.kind == RegionKind::ControlRod {
+                return Some(RegionKind::ControlRod);
+            }
+            found = Some(region.kind);
+        }
+        found
+    }
+
+    fn record_deaths(&mut self, count: u64) {
+        self.deaths += count;
+        self.window_deaths += count;
+    }
+
+    // k = neutrones nacidos / neutrones perdidos en la ventana
+    fn close_window(&mut self, time_step: f64) {
+        self.window_steps += 1;
+        if self.window_steps < MEASUREMENT_WINDOW {
+            return;
+        }
+        if self.window_deaths > 0 {
+            let k = self.window_births as f64 / self.window_deaths as f64;
+            self.multiplication = Some(k);
+            self.history.push((time_step, k));
+            if self.history.len() > HISTORY_LENGTH {
+                self.history.remove(0);
+            }
+        }
+        self.window_steps = 0;
+        self.window_births = 0;
+        self.window_deaths = 0;
+    }
+}
+
+// Qué le pasa a un neutrón libre en este paso
+enum NeutronFate {
+    Absorbed,
+    Fission(usize),
+}
+
+impl Simulation {
+    // Neutrón libre que aún puede provocar una fisión (sin paredes, el que sale del lienzo se pierde)
+    fn neutron_in_play(&self, particle: &Particle) -> bool {
+        let inside = particle.x >= 0.0
+            && particle.x <= self.canvas_width
+            && particle.y >= 0.0
+            && particle.y <= self.canvas_height;
+        particle.ptype == ParticleType::Neutron
+            && particle.orbiting_around.is_none()
+            && (inside || self.boundary != BoundaryMode::Open)
+    }
+
+    // Escenario de reactor: los neutrones libres se frenan en el moderador, mueren en
+    // las barras de control y, si llegan lentos al combustible, provocan fisiones que
+    // liberan nuevos neutrones rápidos
+    pub(crate) fn handle_reactor(&mut self) {
+        let Some(mut reactor) = self.reactor.take() else { return };
+
+        // Neutrones libres del paso anterior que ya no están en juego: eliminados por el
+        // contorno absorbente, fuera del lienzo abierto, desintegrados o capturados por un núcleo
+        let lost = reactor
+            .free_neutrons
+            .iter()
+            .filter(|&&id| !self.get_particle(id).is_some_and(|p| self.neutron_in_play(p)))
+            .count();
+        reactor.record_deaths(lost as u64);
+
+        let mut fates = Vec::new();
+        let mut spent_now = vec![false; reactor.fuel.len()];
+        for particle in &mut self.particles {
+            if particle.ptype != ParticleType::Neutron || particle.orbiting_around.is_some() {
+                continue;
+            }
+
+            match reactor.region_at(particle.x, particle.y) {
+                Some(RegionKind::ControlRod) => {
+                    fates.push((particle.id, NeutronFate::Absorbed));
+                    continue;
+                }
+                Some(RegionKind::Moderator) => {
+                    let speed = particle.dx.hypot(particle.dy);
+                    if speed > reactor.thermal_speed {
+                        let target = speed - (speed - reactor.thermal_speed) * reactor.moderation;
+                        particle.dx *= target / speed;
+                        particle.dy *= target / speed;
+                    }
+                }
+                None => {}
+            }
+
+            let slow = particle.dx.hypot(particle.dy) < 2.0 * reactor.thermal_speed;
+            let site = reactor.fuel.iter().enumerate().position(|(i, site)| {
+                !site.spent && !spent_now[i] && (particle.x - site.x).hypot(particle.y - site.y) < FUEL_RADIUS
+            });
+            if let (Some(site), true) = (site, slow) {
+                if self.rng.next_f64() < PARASITIC_CAPTURE_PROBABILITY {
+                    fates.push((particle.id, NeutronFate::Absorbed));
+                } else {
+                    spent_now[site] = true;
+                    fates.push((particle.id, NeutronFate::Fission(site)));
+                }
+            }
+        }
+
+        for (id, fate) in fates {
+            self.remove_particle(id);
+            reactor.record_deaths(1);
+
+            if let NeutronFate::Fission(site) = fate {
+                let (x, y) = (reactor.fuel[site].x, reactor.fuel[site].y);
+                reactor.fuel[site].spent = true;
+                reactor.fissions += 1;
+
+                let FissionOutcome { released, heavy, light, energy } =
+                    self.sample_fission(FUEL_PROTONS, FUEL_NEUTRONS + 1);
+                for _ in 0..released {
+                    let angle = self.rng.next_angle();
+                    let (dx, dy) = (angle.cos() * reactor.fast_speed, angle.sin() * reactor.fast_speed);
+                    self.add_particle_with_velocity(
+                        x + angle.cos() * (FUEL_RADIUS + 5.0),
+                        y + angle.sin() * (FUEL_RADIUS + 5.0),
+                        ParticleType::Neutron,
+                        dx,
+                        dy,
+                    );
+                }
+                reactor.births += released as u64;
+                reactor.window_births += released as u64;
+
+                self.events.push(SimulationEvent::Fission {
+                    protons: FUEL_PROTONS,
+                    neutrons: FUEL_NEUTRONS,
+                    heavy_fragment: heavy,
+                    light_fragment: light,
+                    neutrons_released: released,
+                    energy,
+                    x,
+                    y,
+                    time_step: self.time_step,
+                });
+            }
+        }
+
+        reactor.free_neutrons = self.particles.iter().filter(|p| self.neutron_in_play(p)).map(|p| p.id).collect();
+        reactor.close_window(self.time_step);
+        self.reactor = Some(reactor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Neutrones térmicos en direcciones aleatorias desde el centro del lienzo
+    fn reactor_scene(reactor: Reactor, neutrons: usize, boundary: BoundaryMode) -> Simulation {
+        let mut simulation = Simulation::new(600.0, 600.0);
+        simulation.set_seed(11);
+        simulation.boundary = boundary;
+        simulation.params.nuclear_attractive = 0.0;
+        for _ in 0..neutrons {
+            let angle = simulation.rng.next_angle();
+            let x = 200.0 + 200.0 * simulation.rng.next_f64();
+            let y = 200.0 + 200.0 * simulation.rng.next_f64();
+            simulation.add_particle_with_velocity(x, y, ParticleType::Neutron, angle.cos(), angle.sin());
+        }
+        simulation.reactor = Some(reactor);
+        simulation
+    }
+
+    fn run(simulation: &mut Simulation, steps: usize) -> &Reactor {
+        for _ in 0..steps {
+            simulation.update();
+        }
+        simulation.reactor.as_ref().unwrap()
+    }
+
+    // Combustible denso y moderador por todo el lienzo
+    fn lattice(spacing: f64) -> Reactor {
+        let mut reactor = Reactor::default();
+        reactor.add_region(RegionKind::Moderator, 0.0, 0.0, 600.0, 600.0);
+        let mut y = spacing / 2.0;
+        while y < 600.0 {
+            let mut x = spacing / 2.0;
+            while x < 600.0 {
+                reactor.add_fuel(x, y);
+                x += spacing;
+            }
+            y += spacing;
+        }
+        reactor
+    }
+
+    #[test]
+    fn dense_moderated_fuel_is_supercritical() {
+        let mut simulation = reactor_scene(lattice(30.0), 20, BoundaryMode::Reflective);
+        let reactor = run(&mut simulation, 100);
+        let k = reactor.history.first().map(|&(_, k)| k).expect("k medida");
+        assert!(k > 1.0, "k = {}", k);
+        assert!(reactor.fissions > 0);
+    }
+
+    #[test]
+    fn control_rods_make_it_subcritical() {
+        let mut reactor = lattice(30.0);
+        for x in (0..6).map(|i| 50.0 + 100.0 * i as f64) {
+            reactor.add_region(RegionKind::ControlRod, x - 25.0, 0.0, 50.0, 600.0);
+        }
+        let mut simulation = reactor_scene(reactor, 20, BoundaryMode::Reflective);
+        let reactor = run(&mut simulation, 100);
+        let k = reactor.history.first().map(|&(_, k)| k).expect("k medida");
+        assert!(k < 1.0, "k = {}", k);
+    }
+
+    #[test]
+    fn leaked_neutrons_count_as_losses() {
+        for boundary in [BoundaryMode::Absorbing, BoundaryMode::Open] {
+            // Sin combustible ni barras: todos acaban saliendo del lienzo
+            let mut simulation = reactor_scene(Reactor::default(), 10, boundary);
+            for particle in &mut simulation.particles {
+                particle.dx *= 10.0;
+                particle.dy *= 10.0;
+            }
+            let reactor = run(&mut simulation, 100);
+            assert_eq!(reactor.deaths, 10, "{:?}", boundary);
+            assert_eq!(reactor.multiplication, Some(0.0));
+        }
+    }
+
+    #[test]
+    fn clear_removes_the_reactor() {
+        let mut simulation = reactor_scene(lattice(60.0), 5, BoundaryMode::Reflective);
+        run(&mut simulation, 10);
+        simulation.clear();
+        assert!(simulation.reactor.is_none());
+    }
+}
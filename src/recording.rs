@@ -0,0 +1,411 @@
+use crate::boundary::BoundaryMode;
+use crate::chemistry::Bond;
+use crate::fission::HeldNucleus;
+use crate::particle::{Particle, ParticleId};
+use crate::physics::PhysicsParams;
+use crate::reactor::Reactor;
+use crate::simulation::Simulation;
+
+// Cambio de una partícula respecto al fotograma anterior
+#[derive(Debug, Clone)]
+enum ParticleChange {
+    // Solo cambió la cinemática (el caso habitual en cada paso)
+    Moved { id: ParticleId, x: f64, y: f64, dx: f64, dy: f64 },
+    // Partícula nueva o con otros campos modificados (órbita, tipo, masa...)
+    Upsert(Particle),
+}
+
+// Resto del estado que se ve al reproducir: enlaces, reactor, contorno, parámetros
+// y núcleos retenidos
+#[derive(Debug, Clone, PartialEq)]
+struct SceneState {
+    params: PhysicsParams,
+    boundary: BoundaryMode,
+    bonds: Vec<Bond>,
+    reactor: Option<Reactor>,
+    held_nuclei: Vec<HeldNucleus>,
+}
+
+impl SceneState {
+    fn of(simulation: &Simulation) -> Self {
+        SceneState {
+            params: simulation.params.clone(),
+            boundary: simulation.boundary,
+            bonds: simulation.bonds.clone(),
+            reactor: simulation.reactor.clone(),
+            held_nuclei: simulation.held_nuclei.clone(),
+        }
+    }
+
+    fn apply(&self, simulation: &mut Simulation) {
+        simulation.params = self.params.clone();
+        simulation.boundary = self.boundary;
+        simulation.bonds = self.bonds.clone();
+        simulation.reactor = self.reactor.clone();
+        simulation.held_nuclei = self.held_nuclei.clone();
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Frame {
+    Key { particles: Vec<Particle>, scene: SceneState, time_step: f64 },
+    // `scene` solo cuando cambió respecto al fotograma anterior
+    Delta { changes: Vec<ParticleChange>, removed: Vec<ParticleId>, scene: Option<SceneState>, time_step: f64 },
+}
+
+// Grabación de una ejecución: un fotograma clave cada `keyframe_interval`
+// fotogramas y deltas de `Simulation::particles` (y de la escena) entre ellos
+#[derive(Debug, Clone)]
+pub struct Recording {
+    canvas_width: f64,
+    canvas_height: f64,
+    keyframe_interval: usize,
+    frames: Vec<Frame>,
+    last: Vec<Particle>, // Estado del último fotograma grabado, para calcular el delta
+    last_scene: Option<SceneState>,
+}
+
+impl Recording {
+    pub fn new(canvas_width: f64, canvas_height: f64, keyframe_interval: usize) -> Self {
+        Recording {
+            canvas_width,
+            canvas_height,
+            keyframe_interval: keyframe_interval.max(1),
+            frames: Vec::new(),
+            last: Vec::new(),
+            last_scene: None,
+        }
+    }
+
+    pub fn record(&mut self, simulation: &Simulation) {
+        let scene = SceneState::of(simulation);
+        let frame = if self.frames.len().is_multiple_of(self.keyframe_interval) {
+            Frame::Key {
+                particles: simulation.particles.clone(),
+                scene: scene.clone(),
+                time_step: simulation.time_step,
+            }
+        } else {
+            let (changes, removed) = diff(&self.last, &simulation.particles);
+            Frame::Delta {
+                changes,
+                removed,
+                scene: (self.last_scene.as_ref() != Some(&scene)).then(|| scene.clone()),
+                time_step: simulation.time_step,
+            }
+        };
+        self.frames.push(frame);
+        self.last = simulation.particles.clone();
+        self.last_scene = Some(scene);
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    // Reconstruye el fotograma `index`: último fotograma clave + deltas posteriores
+    pub fn frame(&self, index: usize) -> Option<Simulation> {
+        if index >= self.frames.len() {
+            return None;
+        }
+
+        let key_index = index - index % self.keyframe_interval;
+        let (mut particles, mut scene, mut time_step) = match &self.frames[key_index] {
+            Frame::Key { particles, scene, time_step } => (particles.clone(), scene, *time_step),
+            Frame::Delta { .. } => return None,
+        };
+
+        for frame in &self.frames[key_index + 1..=index] {
+            if let Frame::Delta { changes, removed, scene: changed, time_step: t } = frame {
+                apply_delta(&mut particles, changes, removed);
+                if let Some(changed) = changed {
+                    scene = changed;
+                }
+                time_step = *t;
+            }
+        }
+
+        let mut simulation = Simulation::new(self.canvas_width, self.canvas_height);
+        scene.apply(&mut simulation);
+        simulation.time_step = time_step;
+        let next_id = particles.last().map_or(0, |p| p.id + 1);
+        simulation.restore_particles(particles, next_id);
+        Some(simulation)
+    }
+}
+
+// Ambos vectores están ordenados por id (invariante de `Simulation`)
+fn diff(previous: &[Particle], current: &[Particle]) -> (Vec<ParticleChange>, Vec<ParticleId>) {
+    let mut changes = Vec::new();
+    let mut removed = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < previous.len() || j < current.len() {
+        match (previous.get(i), current.get(j)) {
+            (Some(old), Some(new)) if old.id == new.id => {
+                if !same_identity(old, new) {
+                    changes.push(ParticleChange::Upsert(new.clone()));
+                } else if old.x != new.x || old.y != new.y || old.dx != new.dx || old.dy != new.dy {
+                    changes.push(ParticleChange::Moved {
+                        id: new.id,
+                        x: new.x,
+                        y: new.y,
+                        dx: new.dx,
+                        dy: new.dy,
+                    });
+                }
+                i += 1;
+                j += 1;
+            }
+            (Some(old), Some(new)) if old.id < new.id => {
+                removed.push(old.id);
+                i += 1;
+            }
+            (Some(old), None) => {
+                removed.push(old.id);
+                i += 1;
+            }
+            (_, Some(new)) => {
+                changes.push(ParticleChange::Upsert(new.clone()));
+                j += 1;
+            }
+            (None, None) => break,
+        }
+    }
+
+    (changes, removed)
+}
+
+// Todos los campos salvo posición y velocidad
+fn same_identity(a: &Particle, b: &Particle) -> bool {
+    a.ptype == b.ptype
+        && a.radius == b.radius
+        && a.charge == b.charge
+        && a.mass == b.mass
+        && a.orbiting_around == b.orbiting_around
+        && a.orbital_position == b.orbital_position
+        && a.energy == b.energy
+}
+
+fn apply_delta(particles: &mut Vec<Particle>, changes: &[ParticleChange], removed: &[ParticleId]) {
+    particles.retain(|p| removed.binary_search(&p.id).is_err());
+
+    for change in changes {
+        match change {
+            ParticleChange::Moved { id, x, y, dx, dy } => {
+                if let Ok(idx) = particles.binary_search_by_key(id, |p| p.id) {
+                    let particle = &mut particles[idx];
+                    particle.x = *x;
+                    particle.y = *y;
+                    particle.dx = *dx;
+                    particle.dy = *dy;
+                }
+            }
+            ParticleChange::Upsert(new) => match particles.binary_search_by_key(&new.id, |p| p.id) {
+                Ok(idx) => particles[idx] = new.clone(),
+                Err(idx) => particles.insert(idx, new.clone()),
+            },
+        }
+    }
+}
+
+// Reproducción de una grabación, independiente de la física en vivo
+pub struct Playback {
+    recording: Recording,
+    position: f64, // Fotograma actual (fraccionario para velocidades no enteras)
+    speed: f64,    // Fotogramas por tick; negativo = hacia atrás
+    paused: bool,
+    current: Simulation,
+}
+
+impl Playback {
+    pub fn new(recording: Recording) -> Self {
+        let current = recording
+            .frame(0)
+            .unwrap_or_else(|| Simulation::new(recording.canvas_width, recording.canvas_height));
+        Playback {
+            recording,
+            position: 0.0,
+            speed: 1.0,
+            paused: false,
+            current,
+        }
+    }
+
+    // Un tick de reproducción: avanza `speed` fotogramas salvo en pausa
+    pub fn advance(&mut self) {
+        if self.paused || self.recording.frame_count() == 0 {
+            return;
+        }
+        let last = (self.recording.frame_count() - 1) as f64;
+        let position = (self.position + self.speed).clamp(0.0, last);
+        self.set_position(position);
+    }
+
+    pub fn seek(&mut self, frame: usize) {
+        if self.recording.frame_count() == 0 {
+            return;
+        }
+        let frame = frame.min(self.recording.frame_count() - 1);
+        self.set_position(frame as f64);
+    }
+
+    fn set_position(&mut self, position: f64) {
+        let previous = self.current_frame();
+        self.position = position;
+        let frame = self.current_frame();
+        if frame != previous {
+            if let Some(simulation) = self.recording.frame(frame) {
+                self.current = simulation;
+            }
+        }
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.position.floor() as usize
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.recording.frame_count()
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Escena del fotograma actual (para renderizar o consultar núcleos)
+    pub fn simulation(&self) -> &Simulation {
+        &self.current
+    }
+
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleType;
+    use crate::reactor::RegionKind;
+
+    // Graba `steps` pasos de una escena con enlaces, reactor y contorno no predeterminado;
+    // devuelve también el estado en vivo de cada fotograma para comparar
+    fn recorded_run(steps: usize, keyframe_interval: usize) -> (Recording, Vec<String>) {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.params.bonding_enabled = true;
+        simulation.boundary = BoundaryMode::Periodic;
+        simulation.add_particle(300.0, 300.0, ParticleType::Proton);
+        simulation.add_particle(350.0, 300.0, ParticleType::Electron);
+        simulation.add_particle(420.0, 300.0, ParticleType::Proton);
+        simulation.add_particle(470.0, 300.0, ParticleType::Electron);
+        simulation.add_particle_with_velocity(100.0, 500.0, ParticleType::Neutron, 2.0, 0.0);
+        let mut reactor = Reactor::default();
+        reactor.add_region(RegionKind::Moderator, 50.0, 450.0, 200.0, 100.0);
+        simulation.reactor = Some(reactor);
+
+        let mut recording = Recording::new(800.0, 600.0, keyframe_interval);
+        let mut states = Vec::new();
+        for step in 0..steps {
+            if step == steps / 2 {
+                simulation.params.dt = 0.5; // Cambio de parámetros a mitad de grabación
+                simulation.add_particle(600.0, 100.0, ParticleType::Electron);
+                let first = simulation.particles[0].id;
+                simulation.remove_particle(first);
+            }
+            recording.record(&simulation);
+            states.push(simulation.to_json().unwrap());
+            simulation.update();
+        }
+        (recording, states)
+    }
+
+    // Estado visible (sin el generador aleatorio, que no se graba)
+    fn visible(json: &str) -> serde_json::Value {
+        let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("rng");
+        object.remove("next_id");
+        value
+    }
+
+    #[test]
+    fn every_frame_reproduces_the_live_scene() {
+        let (recording, states) = recorded_run(30, 7);
+        assert_eq!(recording.frame_count(), 30);
+        for (index, state) in states.iter().enumerate() {
+            let frame = recording.frame(index).unwrap();
+            assert_eq!(visible(&frame.to_json().unwrap()), visible(state), "fotograma {}", index);
+        }
+        assert!(recording.frame(30).is_none());
+    }
+
+    #[test]
+    fn frames_keep_bonds_reactor_boundary_and_params() {
+        let (recording, _) = recorded_run(30, 7);
+        assert!(!recording.frame(10).unwrap().bonds.is_empty());
+        let frame = recording.frame(20).unwrap();
+        assert!(frame.bonds.is_empty()); // Se rompió al quitar uno de los protones
+        assert_eq!(frame.reactor.as_ref().map(|r| r.regions.len()), Some(1));
+        assert_eq!(frame.boundary, BoundaryMode::Periodic);
+        assert!(frame.params.bonding_enabled);
+        assert_eq!(frame.params.dt, 0.5);
+        assert_eq!(recording.frame(3).unwrap().params.dt, 1.0);
+    }
+
+    #[test]
+    fn seek_and_scrub_jump_to_the_requested_frame() {
+        let (recording, states) = recorded_run(30, 7);
+        let mut playback = Playback::new(recording);
+        for frame in [17, 3, 29, 0, 8, 8] {
+            playback.seek(frame);
+            assert_eq!(playback.current_frame(), frame);
+            assert_eq!(visible(&playback.simulation().to_json().unwrap()), visible(&states[frame]));
+        }
+        playback.seek(500);
+        assert_eq!(playback.current_frame(), 29);
+    }
+
+    #[test]
+    fn reverse_playback_walks_back_and_stops_at_the_start() {
+        let (recording, states) = recorded_run(12, 5);
+        let mut playback = Playback::new(recording);
+        playback.seek(11);
+        playback.set_speed(-1.0);
+        for expected in (0..11).rev() {
+            playback.advance();
+            assert_eq!(playback.current_frame(), expected);
+            assert_eq!(visible(&playback.simulation().to_json().unwrap()), visible(&states[expected]));
+        }
+        playback.advance();
+        assert_eq!(playback.current_frame(), 0);
+    }
+
+    #[test]
+    fn fractional_speed_and_pause() {
+        let (recording, _) = recorded_run(10, 4);
+        let mut playback = Playback::new(recording);
+        playback.set_speed(0.5);
+        playback.advance();
+        assert_eq!(playback.current_frame(), 0);
+        playback.advance();
+        assert_eq!(playback.current_frame(), 1);
+
+        playback.set_paused(true);
+        playback.advance();
+        assert_eq!(playback.current_frame(), 1);
+        assert_eq!(playback.into_recording().frame_count(), 10);
+    }
+}
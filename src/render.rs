@@ -0,0 +1,819 @@
+use crate::atomic::photon_wavelength;
+use crate::chemistry::BondKind;
+use crate::particle::ParticleType;
+use crate::reactor::{Reactor, RegionKind, FUEL_RADIUS};
+use crate::renderer::Renderer;
+use crate::simulation::{Nucleus, Simulation};
+use crate::svg::SvgRenderer;
+
+// Dibujo de la escena sobre cualquier backend `Renderer`
+impl Simulation {
+    // Misma escena que `render`, como documento SVG independiente
+    pub fn export_svg(&self) -> String {
+        let mut renderer = SvgRenderer::new(self.canvas_width, self.canvas_height);
+        self.render(&mut renderer);
+        renderer.finish()
+    }
+
+    pub fn render(&self, renderer: &mut dyn Renderer) {
+        // Regiones y combustible del reactor, debajo de todo lo demás
+        if let Some(reactor) = &self.reactor {
+            self.draw_reactor(renderer, reactor);
+        }
+        if !self.bonds.is_empty() {
+            self.draw_bonds(renderer);
+        }
+
+        // Detectar núcleos y crear un conjunto de índices de partículas en núcleos
+        let nuclei = self.detect_nuclei();
+        let mut nucleus_particle_indices = std::collections::HashSet::new();
+
+        for nucleus in &nuclei {
+            for &proton_idx in &nucleus.protons {
+                nucleus_particle_indices.insert(proton_idx);
+            }
+            for &neutron_idx in &nucleus.neutrons {
+                nucleus_particle_indices.insert(neutron_idx);
+            }
+        }
+
+        // Dibujar núcleos detectados y orbitales cuánticos SOLO si hay electrones en ese nivel
+        for nucleus in &nuclei {
+            if let Some(proton_idx) = nucleus.protons.first() {
+                if let Some(_proton) = self.particles.get(*proton_idx) {
+                    // Calcular centro del núcleo
+                    let mut center_x = 0.0;
+                    let mut center_y = 0.0;
+                    let proton_count = nucleus.protons.len();
+
+                    for &p_idx in &nucleus.protons {
+                        if let Some(p) = self.particles.get(p_idx) {
+                            center_x += p.x;
+                            center_y += p.y;
+                        }
+                    }
+
+                    if proton_count > 0 {
+                        center_x /= proton_count as f64;
+                        center_y /= proton_count as f64;
+
+                        // Contar electrones por orbital según el nuevo sistema spdf
+                        let mut e_1s = 0;
+                        let mut e_2s = 0;
+                        let mut e_2p = 0;
+                        let mut e_3s = 0;
+                        let mut e_3p = 0;
+                        let mut e_4s = 0;
+                        let mut e_3d = 0;
+                        let mut e_4f = 0;
+
+                        for &e_idx in &nucleus.electrons {
+                            if let Some(e) = self.particles.get(e_idx) {
+                                if let Some((subnivel, principal_n, _orbital_idx, _pos_in_orbital)) = e.orbital_position {
+                                    match (principal_n, subnivel) {
+                                        (1, 's') => e_1s += 1,
+                                        (2, 's') => e_2s += 1,
+                                        (2, 'p') => e_2p += 1,
+                                        (3, 's') => e_3s += 1,
+                                        (3, 'p') => e_3p += 1,
+                                        (4, 's') => e_4s += 1,
+                                        (3, 'd') => e_3d += 1,
+                                        (4, 'f') => e_4f += 1,
+                                        _ => {}
+                                    }
+                                }
+                            }
+                        }
+
+                        // Visualización explícita de subniveles spdf con formas características
+                        let orbital_visualizations = [
+                            ("1s", e_1s, 60.0, "rgba(255,152,0,0.4)", "s"),     // naranja - esfera
+                            ("2s", e_2s, 90.0, "rgba(255,255,0,0.4)", "s"),    // amarillo - esfera más grande
+                            ("2p", e_2p, 120.0, "rgba(76,175,80,0.4)", "p"),   // verde - formas de huso
+                            ("3s", e_3s, 150.0, "rgba(156,39,176,0.4)", "s"),  // púrpura - esfera
+                            ("3p", e_3p, 180.0, "rgba(33,150,243,0.4)", "p"),  // azul - husos
+                            ("4s", e_4s, 210.0, "rgba(255,87,34,0.4)", "s"),   // naranja rojo - esfera
+                            ("3d", e_3d, 240.0, "rgba(142,36,170,0.4)", "d"),  // violeta - formas de trébol
+                            ("4f", e_4f, 280.0, "rgba(0,188,212,0.4)", "f"),   // cyan - formas complejas
+                        ];
+                        for (sublevel_name, e_count, radius, color, kind) in orbital_visualizations.iter() {
+                            if *e_count > 0 {
+                                // Visualizar formas características de cada subnivel
+                                self.draw_subnivel_shape(renderer, center_x, center_y, *radius, color, kind, *e_count);
+
+                                // Etiqueta del subnivel con número de electrones
+                                renderer.set_fill_style("#ffffff");
+                                renderer.set_font("bold 12px Arial");
+                                renderer.set_text_align("center");
+                                let label = format!("{}({}e⁻)", sublevel_name, e_count);
+                                let label_y = match *kind {
+                                    "s" => center_y + *radius + 20.0,
+                                    "p" => center_y + *radius + 40.0,
+                                    "d" => center_y + *radius + 60.0,
+                                    "f" => center_y + *radius + 80.0,
+                                    _ => center_y + *radius + 20.0,
+                                };
+                                renderer.fill_text(&label, center_x, label_y);
+                            }
+                        }
+
+                        // Dibujar círculo de fondo del elemento con color único y 50% transparencia
+                        self.draw_element_background(renderer, center_x, center_y, nucleus.protons.len());
+
+                        // Dibujar letra del elemento en el centro del núcleo
+                        self.draw_element_symbol(renderer, center_x, center_y, nucleus);
+                    }
+                }
+            }
+        }
+
+        // Dibujar partículas
+        for (idx, particle) in self.particles.iter().enumerate() {
+            let in_nucleus = nucleus_particle_indices.contains(&idx);
+
+            // Dibujar círculo principal
+            renderer.begin_path();
+            let draw_radius = match particle.ptype {
+                ParticleType::Electron | ParticleType::Positron => particle.radius / 3.0,
+                _ => particle.radius,
+            };
+            renderer.arc(
+                particle.x,
+                particle.y,
+                draw_radius,
+                0.0,
+                2.0 * std::f64::consts::PI,
+            );
+
+            match particle.ptype {
+                ParticleType::Proton => {
+                    let color = if in_nucleus {
+                        "rgba(255, 0, 0, 0.05)"  // 95% transparencia (5% opacidad)
+                    } else {
+                        "#ff0000"
+                    };
+                    renderer.set_stroke_style(color);
+                    renderer.set_line_width(2.0);
+                    renderer.stroke();
+                    // Dibujar quarks (up, up, down) para protón
+                    if !in_nucleus {
+                        self.draw_quarks(renderer, particle.x, particle.y, particle.radius, &["up", "up", "down"]);
+                    }
+                }
+                ParticleType::Neutron => {
+                    let color = if in_nucleus {
+                        "rgba(255, 255, 255, 0.05)"  // 95% transparencia
+                    } else {
+                        "#ffffff"
+                    };
+                    renderer.set_stroke_style(color);
+                    renderer.set_line_width(2.0);
+                    renderer.stroke();
+                    // Dibujar quarks (up, down, down) para neutrón
+                    if !in_nucleus {
+                        self.draw_quarks(renderer, particle.x, particle.y, particle.radius, &["up", "down", "down"]);
+                    }
+                }
+                ParticleType::Electron => {
+                    // Color según orbital cuántico
+                    let color = if let Some((subnivel, principal_n, _orbital_idx, _pos_in_orbital)) = particle.orbital_position {
+                        match (principal_n, subnivel) {
+                            (1, 's') => "#ff9800",  // 1s: naranja
+                            (2, 's') => "#ffff00",  // 2s: amarillo
+                            (2, 'p') => "#4caf50",  // 2p: verde
+                            (3, 's') => "#9c27b0",  // 3s: púrpura
+                            (3, 'p') => "#2196f3",  // 3p: azul
+                            (4, 's') => "#ff5722",  // 4s: naranja rojo
+                            (3, 'd') => "#8e24aa",  // 3d: violeta
+                            (4, 'f') => "#00bcd4",  // 4f: cyan
+                            (5, 's') => "#795548",  // 5s: café
+                            (4, 'd') => "#607d8b",  // 4d: azul gris
+                            (5, 'p') => "#e91e63",  // 5p: rosa
+                            (6, 's') => "#9e9e9e",  // 6s: gris
+                            _ => "#ffffff",
+                        }
+                    } else {
+                        "#ffffff" // Libre
+                    };
+                    renderer.set_fill_style(color);
+                    renderer.fill();
+                }
+                ParticleType::Positron => {
+                    renderer.set_fill_style("#ff4081"); // Rosa: antimateria
+                    renderer.fill();
+                }
+                ParticleType::Photon => {
+                    // Color de su longitud de onda, con una estela en la dirección de avance
+                    let color = Self::wavelength_color(photon_wavelength(particle.energy));
+                    renderer.set_fill_style(&color);
+                    renderer.fill();
+                    let speed = particle.dx.hypot(particle.dy).max(1e-9);
+                    renderer.begin_path();
+                    renderer.ellipse(
+                        particle.x - particle.dx / speed * draw_radius * 2.0,
+                        particle.y - particle.dy / speed * draw_radius * 2.0,
+                        draw_radius * 2.0,
+                        draw_radius * 0.5,
+                        particle.dy.atan2(particle.dx),
+                        0.0,
+                        2.0 * std::f64::consts::PI,
+                    );
+                    renderer.set_stroke_style(&color);
+                    renderer.set_line_width(1.0);
+                    renderer.stroke();
+                }
+            }
+        }
+    }
+
+    pub(crate) fn get_element_symbol(proton_count: usize) -> &'static str {
+        match proton_count {
+            1 => "H",   // Hidrógeno
+            2 => "He",  // Helio
+            3 => "Li",  // Litio
+            4 => "Be",  // Berilio
+            5 => "B",   // Boro
+            6 => "C",   // Carbono
+            7 => "N",   // Nitrógeno
+            8 => "O",   // Oxígeno
+            9 => "F",   // Flúor
+            10 => "Ne",  // Neón
+            11 => "Na", // Sodio
+            12 => "Mg", // Magnesio
+            13 => "Al", // Aluminio
+            14 => "Si", // Silicio
+            15 => "P",  // Fósforo
+            16 => "S",  // Azufre
+            17 => "Cl", // Cloro
+            18 => "Ar", // Argón
+            19 => "K",  // Potasio
+            20 => "Ca", // Calcio
+            21 => "Sc", // Escandio
+            22 => "Ti", // Titanio
+            23 => "V",  // Vanadio
+            24 => "Cr", // Cromo
+            25 => "Mn", // Manganeso
+            26 => "Fe", // Hierro
+            27 => "Co", // Cobalto
+            28 => "Ni", // Níquel
+            29 => "Cu", // Cobre
+            30 => "Zn", // Zinc
+            31 => "Ga", // Galio
+            32 => "Ge", // Germanio
+            33 => "As", // Arsénico
+            34 => "Se", // Selenio
+            35 => "Br", // Bromo
+            36 => "Kr", // Kriptón
+            37 => "Rb", // Rubidio
+            38 => "Sr", // Estroncio
+            39 => "Y",  // Itrio
+            40 => "Zr", // Circonio
+            41 => "Nb", // Niobio
+            42 => "Mo", // Molibdeno
+            43 => "Tc", // Tecnecio
+            44 => "Ru", // Rutenio
+            45 => "Rh", // Rodio
+            46 => "Pd", // Paladio
+            47 => "Ag", // Plata
+            48 => "Cd", // Cadmio
+            49 => "In", // Indio
+            50 => "Sn", // Estaño
+            51 => "Sb", // Antimonio
+            52 => "Te", // Telurio
+            53 => "I",  // Yodo
+            54 => "Xe", // Xenón
+            55 => "Cs", // Cesio
+            56 => "Ba", // Bario
+            57 => "La", // Lantano
+            58 => "Ce", // Cerio
+            59 => "Pr", // Praseodimio
+            60 => "Nd", // Neodimio
+            61 => "Pm", // Prometio
+            62 => "Sm", // Samario
+            63 => "Eu", // Europio
+            64 => "Gd", // Gadolinio
+            65 => "Tb", // Terbio
+            66 => "Dy", // Disprosio
+            67 => "Ho", // Holmio
+            68 => "Er", // Erbio
+            69 => "Tm", // Tulio
+            70 => "Yb", // Iterbio
+            71 => "Lu", // Lutecio
+            72 => "Hf", // Hafnio
+            73 => "Ta", // Tántalo
+            74 => "W",  // Tungsteno
+            75 => "Re", // Renio
+            76 => "Os", // Osmio
+            77 => "Ir", // Iridio
+            78 => "Pt", // Platino
+            79 => "Au", // Oro
+            80 => "Hg", // Mercurio
+            81 => "Tl", // Talio
+            82 => "Pb", // Plomo
+            83 => "Bi", // Bismuto
+            84 => "Po", // Polonio
+            85 => "At", // Astato
+            86 => "Rn", // Radón
+            87 => "Fr", // Francio
+            88 => "Ra", // Radio
+            89 => "Ac", // Actinio
+            90 => "Th", // Torio
+            91 => "Pa", // Protactinio
+            92 => "U",  // Uranio
+            93 => "Np", // Neptunio
+            94 => "Pu", // Plutonio
+            95 => "Am", // Americio
+            96 => "Cm", // Curio
+            97 => "Bk", // Berkelio
+            98 => "Cf", // Californio
+            99 => "Es", // Einstenio
+            100 => "Fm", // Fermio
+            101 => "Md", // Mendelevio
+            102 => "No", // Nobelio
+            103 => "Lr", // Laurencio
+            104 => "Rf", // Rutherfordio
+            105 => "Db", // Dubnio
+            106 => "Sg", // Seaborgio
+            107 => "Bh", // Bohrio
+            108 => "Hs", // Hassio
+            109 => "Mt", // Meitnerio
+            110 => "Ds", // Darmstadtio
+            111 => "Rg", // Roentgenio
+            112 => "Cn", // Copernicio
+            113 => "Nh", // Nihonio
+            114 => "Fl", // Flerovio
+            115 => "Mc", // Moscovio
+            116 => "Lv", // Livermorio
+            117 => "Ts", // Tenesino
+            118 => "Og", // Oganesson
+            _ => "Unknown", // Para elementos más allá de 118
+        }
+    }
+
+    fn get_element_color(proton_count: usize) -> &'static str {
+        // Colores únicos para cada elemento con 50% transparencia
+        match proton_count {
+            1 => "rgba(255, 0, 127, 0.5)",     // Magenta para H
+            2 => "rgba(0, 255, 255, 0.5)",     // Cian para He
+            3 => "rgba(0, 255, 0, 0.5)",       // Verde para Li
+            4 => "rgba(255, 165, 0, 0.5)",     // Naranja para Be
+            5 => "rgba(255, 0, 255, 0.5)",     // Magenta para B
+            6 => "rgba(255, 255, 0, 0.5)",     // Amarillo para C
+            7 => "rgba(255, 20, 147, 0.5)",    // Rosa para N
+            8 => "rgba(0, 191, 255, 0.5)",     // Azul para O
+            9 => "rgba(127, 255, 0, 0.5)",     // Verde lima para F
+            10 => "rgba(255, 105, 180, 0.5)",  // Rosa para Ne
+            11 => "rgba(255, 0, 0, 0.5)",       // Rojo para Na
+            12 => "rgba(192, 192, 192, 0.5)",  // Plata para Mg
+            13 => "rgba(218, 165, 32, 0.5)",   // Dorado para Al
+            14 => "rgba(128, 128, 128, 0.5)",  // Gris para Si
+            15 => "rgba(255, 165, 0, 0.5)",    // Naranja para P
+            16 => "rgba(255, 255, 0, 0.5)",    // Amarillo para S
+            17 => "rgba(0, 255, 0, 0.5)",      // Verde para Cl
+            18 => "rgba(138, 43, 226, 0.5)",   // Púrpura para Ar
+            19 => "rgba(255, 0, 0, 0.5)",      // Rojo para K
+            20 => "rgba(255, 255, 255, 0.5)",  // Blanco para Ca
+            26 => "rgba(184, 134, 11, 0.5)",   // Marrón oscuro para Fe
+            29 => "rgba(184, 115, 51, 0.5)",   // Cobriz para Cu
+            47 => "rgba(192, 192, 192, 0.5)",  // Plata para Ag
+            53 => "rgba(128, 0, 128, 0.5)",     // Púrpura oscuro para I
+            54 => "rgba(0, 255, 255, 0.5)",     // Cian para Xe
+            79 => "rgba(255, 215, 0, 0.5)",     // Oro para Au
+            80 => "rgba(192, 192, 192, 0.5)",  // Plata para Hg
+            82 => "rgba(128, 128, 128, 0.5)",  // Gris para Pb
+            86 => "rgba(220, 20, 60, 0.5)",    // Carmesí para Rn
+            88 => "rgba(0, 255, 0, 0.5)",      // Verde para Ra
+            92 => "rgba(0, 191, 255, 0.5)",     // Azul para U
+            _ => "rgba(255, 255, 255, 0.5)",   // Blanco por defecto
+        }
+    }
+
+    fn draw_reactor(&self, renderer: &mut dyn Renderer, reactor: &Reactor) {
+        for region in &reactor.regions {
+            let (fill, stroke) = match region.kind {
+                RegionKind::Moderator => ("rgba(33,150,243,0.15)", "rgba(33,150,243,0.6)"),
+                RegionKind::ControlRod => ("rgba(90,90,90,0.6)", "#9e9e9e"),
+            };
+            renderer.begin_path();
+            renderer.rect(region.x, region.y, region.width, region.height);
+            renderer.set_fill_style(fill);
+            renderer.fill();
+            renderer.set_stroke_style(stroke);
+            renderer.set_line_width(1.0);
+            renderer.stroke();
+        }
+
+        // Combustible: verde si aún es fisible, gris si ya se ha gastado
+        for site in &reactor.fuel {
+            renderer.begin_path();
+            renderer.arc(site.x, site.y, FUEL_RADIUS, 0.0, 2.0 * std::f64::consts::PI);
+            renderer.set_fill_style(if site.spent { "rgba(120,120,120,0.5)" } else { "rgba(76,175,80,0.7)" });
+            renderer.fill();
+        }
+    }
+
+    // Enlaces entre centros de átomo: una línea por orden de enlace (covalente) o una
+    // línea discontinua dorada (iónico); la fórmula de cada molécula bajo su centro
+    fn draw_bonds(&self, renderer: &mut dyn Renderer) {
+        let atoms = self.chemical_atoms();
+        let centers: std::collections::HashMap<_, _> = atoms.iter().map(|atom| (atom.anchor, (atom.x, atom.y))).collect();
+
+        for bond in &self.bonds {
+            let (Some(&(ax, ay)), Some(&(bx, by))) = (centers.get(&bond.atoms.0), centers.get(&bond.atoms.1)) else {
+                continue;
+            };
+            let length = (bx - ax).hypot(by - ay).max(1.0);
+            let (nx, ny) = (-(by - ay) / length, (bx - ax) / length);
+            let spacing = 5.0;
+
+            match bond.kind {
+                BondKind::Covalent => {
+                    renderer.set_stroke_style("rgba(255,255,255,0.7)");
+                    renderer.set_line_width(2.0);
+                    for k in 0..bond.order {
+                        let offset = (k as f64 - (bond.order - 1) as f64 / 2.0) * spacing;
+                        renderer.begin_path();
+                        renderer.move_to(ax + nx * offset, ay + ny * offset);
+                        renderer.line_to(bx + nx * offset, by + ny * offset);
+                        renderer.stroke();
+                    }
+                }
+                BondKind::Ionic => {
+                    renderer.set_stroke_style("rgba(255,215,0,0.7)");
+                    renderer.set_line_width(2.0);
+                    renderer.set_line_dash(&[6.0, 4.0]);
+                    renderer.begin_path();
+                    renderer.move_to(ax, ay);
+                    renderer.line_to(bx, by);
+                    renderer.stroke();
+                    renderer.set_line_dash(&[]);
+                }
+            }
+        }
+
+        renderer.set_fill_style("#ffeb3b");
+        renderer.set_font("bold 14px Arial");
+        renderer.set_text_align("center");
+        renderer.set_text_baseline("top");
+        for molecule in self.detect_molecules() {
+            renderer.fill_text(&molecule.formula, molecule.x, molecule.y + 30.0);
+        }
+    }
+
+    // Banda espectral del núcleo `nucleus_index` (orden de `detect_nuclei`) al pie del
+    // lienzo: el visible de 380 a 780 nm atenuado y encima sus líneas de emisión, con la
+    // altura proporcional a la intensidad relativa
+    pub fn render_spectrum(&self, renderer: &mut dyn Renderer, nucleus_index: usize) {
+        let Some(nucleus) = self.detect_nuclei().into_iter().nth(nucleus_index) else { return };
+        let spectrum = self.spectrum(&nucleus);
+
+        let (min_wavelength, max_wavelength) = (380.0, 780.0);
+        let margin = 10.0;
+        let height = 60.0;
+        let (left, top) = (margin, self.canvas_height - height - margin);
+        let width = self.canvas_width - 2.0 * margin;
+        let x_of = |wavelength: f64| left + (wavelength - min_wavelength) / (max_wavelength - min_wavelength) * width;
+
+        // Fondo: el espectro continuo en franjas de 5 nm, oscurecido
+        let band = 5.0;
+        let mut wavelength = min_wavelength;
+        while wavelength < max_wavelength {
+            renderer.begin_path();
+            renderer.rect(x_of(wavelength), top, x_of(wavelength + band) - x_of(wavelength), height);
+            renderer.set_fill_style(&Self::wavelength_color(wavelength + band / 2.0));
+            renderer.fill();
+            wavelength += band;
+        }
+        renderer.begin_path();
+        renderer.rect(left, top, width, height);
+        renderer.set_fill_style("rgba(0,0,0,0.8)");
+        renderer.fill();
+
+        for line in spectrum.lines.iter().filter(|line| (min_wavelength..=max_wavelength).contains(&line.wavelength)) {
+            let x = x_of(line.wavelength);
+            let line_height = height * line.intensity.max(0.1);
+            renderer.begin_path();
+            renderer.rect(x - 1.0, top + height - line_height, 2.0, line_height);
+            renderer.set_fill_style(&Self::wavelength_color(line.wavelength));
+            renderer.fill();
+        }
+
+        renderer.begin_path();
+        renderer.rect(left, top, width, height);
+        renderer.set_stroke_style("#ffffff");
+        renderer.set_line_width(1.0);
+        renderer.stroke();
+
+        // Escala en nm y configuración electrónica
+        renderer.set_fill_style("#ffffff");
+        renderer.set_font("10px Arial");
+        renderer.set_text_align("center");
+        renderer.set_text_baseline("bottom");
+        for tick in [400.0, 500.0, 600.0, 700.0] {
+            renderer.fill_text(&format!("{} nm", tick), x_of(tick), top - 2.0);
+        }
+        renderer.set_text_align("left");
+        renderer.set_text_baseline("top");
+        renderer.set_font("bold 12px Arial");
+        let label = format!("{} {}", Self::get_element_symbol(spectrum.protons), spectrum.configuration);
+        renderer.fill_text(&label, left + 4.0, top + 4.0);
+    }
+
+    // Color aproximado de una longitud de onda (nm) en el espectro visible (380-780 nm);
+    // el ultravioleta se dibuja violeta tenue y el infrarrojo rojo oscuro
+    fn wavelength_color(wavelength: f64) -> String {
+        if !(380.0..=780.0).contains(&wavelength) {
+            return if wavelength < 380.0 { "rgba(160,80,255,0.6)" } else { "rgba(140,0,0,0.6)" }.to_string();
+        }
+        let (r, g, b) = match wavelength {
+            w if w < 440.0 => ((440.0 - w) / 60.0, 0.0, 1.0),
+            w if w < 490.0 => (0.0, (w - 440.0) / 50.0, 1.0),
+            w if w < 510.0 => (0.0, 1.0, (510.0 - w) / 20.0),
+            w if w < 580.0 => ((w - 510.0) / 70.0, 1.0, 0.0),
+            w if w < 645.0 => (1.0, (645.0 - w) / 65.0, 0.0),
+            _ => (1.0, 0.0, 0.0),
+        };
+        // Atenuación en los bordes, donde el ojo es menos sensible
+        let intensity = match wavelength {
+            w if w < 420.0 => 0.3 + 0.7 * (w - 380.0) / 40.0,
+            w if w > 700.0 => 0.3 + 0.7 * (780.0 - w) / 80.0,
+            _ => 1.0,
+        };
+        let channel = |c: f64| (255.0 * c * intensity).round() as u8;
+        format!("rgb({},{},{})", channel(r), channel(g), channel(b))
+    }
+
+    fn draw_element_background(&self, renderer: &mut dyn Renderer, x: f64, y: f64, proton_count: usize) {
+        let radius = 20.0; // Tamaño similar a protones/neutrones
+        renderer.begin_path();
+        renderer.arc(x, y, radius, 0.0, 2.0 * std::f64::consts::PI);
+        renderer.set_fill_style(Self::get_element_color(proton_count));
+        renderer.fill();
+        // Agregar contorno para mejor visibilidad
+        renderer.set_stroke_style("#ffffff");
+        renderer.set_line_width(1.0);
+        renderer.stroke();
+    }
+
+    fn draw_element_symbol(&self, renderer: &mut dyn Renderer, center_x: f64, center_y: f64, nucleus: &Nucleus) {
+        // Carga real del ion: protones - electrones ligados a este núcleo
+        let charge = self.ion_charge(nucleus);
+
+        let symbol = Self::get_element_symbol(nucleus.protons.len());
+        let display_text = if charge != 0 {
+            let charge_abs = charge.abs();
+            if charge_abs > 3 {
+                // Cargas grandes en notación compacta (Fe5+, O4-)
+                format!("{}{}{}", symbol, charge_abs, if charge > 0 { "+" } else { "-" })
+            } else {
+                format!("{}{}", symbol, if charge > 0 { "+" } else { "-" }.to_string().repeat(charge_abs as usize))
+            }
+        } else {
+            symbol.to_string()
+        };
+
+        // Texto grande y legible en el centro exacto del núcleo
+        renderer.set_fill_style("#ffffff"); // Texto blanco brillante
+        renderer.set_font("bold 20px Arial"); // Tamaño más grande para legibilidad
+        renderer.set_text_align("center");
+        renderer.set_text_baseline("middle");
+
+        renderer.fill_text(&display_text, center_x, center_y);
+    }
+
+    // Dibujar formas características de los subniveles orbitales
+    #[allow(clippy::too_many_arguments)]
+    fn draw_subnivel_shape(&self, renderer: &mut dyn Renderer, center_x: f64, center_y: f64, radius: f64, color: &str, kind: &str, electron_count: usize) {
+        renderer.set_stroke_style(color);
+        renderer.set_line_width(2.0);
+
+        match kind {
+            "s" => {
+                // Subnivel s: forma esférica simétrica
+                renderer.set_line_dash(&[4.0, 2.0]);
+                renderer.begin_path();
+                renderer.arc(center_x, center_y, radius, 0.0, 2.0 * std::f64::consts::PI);
+                renderer.stroke();
+
+                // Dibujar capa interna para mostrar densidad de probabilidad
+                renderer.set_line_dash(&[]);
+                renderer.set_line_width(1.0);
+                renderer.begin_path();
+                renderer.arc(center_x, center_y, radius * 0.7, 0.0, 2.0 * std::f64::consts::PI);
+                renderer.stroke();
+            },
+
+            "p" => {
+                // Subnivel p: tres lóbulos orientados (px, py, pz)
+                renderer.set_line_dash(&[3.0, 2.0]);
+                for (i, angle) in [0.0, std::f64::consts::PI/3.0, 2.0*std::f64::consts::PI/3.0].iter().enumerate() {
+                    if i < electron_count.min(3) {
+                        let lobe_angle = *angle;
+                        let lobe_x = center_x + lobe_angle.cos() * radius * 0.8;
+                        let lobe_y = center_y + lobe_angle.sin() * radius * 0.8;
+
+                        // Dibujar lóbulo en forma de 8
+                        renderer.begin_path();
+                        renderer.ellipse(
+                            lobe_x, lobe_y,
+                            radius * 0.3, radius * 0.15,
+                            lobe_angle,
+                            0.0, 2.0 * std::f64::consts::PI
+                        );
+                        renderer.stroke();
+
+                        // Lóbulo opuesto (signo negativo)
+                        let opposite_x = center_x - lobe_angle.cos() * radius * 0.8;
+                        let opposite_y = center_y - lobe_angle.sin() * radius * 0.8;
+                        renderer.begin_path();
+                        renderer.ellipse(
+                            opposite_x, opposite_y,
+                            radius * 0.3, radius * 0.15,
+                            lobe_angle + std::f64::consts::PI,
+                            0.0, 2.0 * std::f64::consts::PI
+                        );
+                        renderer.stroke();
+                    }
+                }
+            },
+
+            "d" => {
+                // Subnivel d: cinco lóbulos en forma de trébol
+                renderer.set_line_dash(&[2.0, 2.0]);
+                for i in 0..electron_count.min(5) {
+                    let angle = (i as f64) * 2.0 * std::f64::consts::PI / 5.0;
+                    let lobe_x = center_x + angle.cos() * radius * 0.6;
+                    let lobe_y = center_y + angle.sin() * radius * 0.6;
+
+                    // Lóbulos con forma de pétalo
+                    renderer.begin_path();
+                    renderer.ellipse(
+                        lobe_x, lobe_y,
+                        radius * 0.25, radius * 0.12,
+                        angle + std::f64::consts::PI/2.0,
+                        0.0, 2.0 * std::f64::consts::PI
+                    );
+                    renderer.stroke();
+
+                    // Lóbulo perpendicular
+                    let perp_x = center_x + angle.cos() * radius * 0.6;
+                    let perp_y = center_y + angle.sin() * radius * 0.6;
+                    renderer.begin_path();
+                    renderer.ellipse(
+                        perp_x, perp_y,
+                        radius * 0.15, radius * 0.25,
+                        angle,
+                        0.0, 2.0 * std::f64::consts::PI
+                    );
+                    renderer.stroke();
+                }
+            },
+
+            "f" => {
+                // Subnivel f: siete lóbulos con formas complejas
+                renderer.set_line_dash(&[1.0, 1.0]);
+                for i in 0..electron_count.min(7) {
+                    let base_angle = (i as f64) * 2.0 * std::f64::consts::PI / 7.0;
+
+                    // Forma compleja con múltiples lóbulos pequeños
+                    for j in 0..3 {
+                        let offset_angle = (j as f64) * std::f64::consts::PI / 6.0;
+                        let lobe_angle = base_angle + offset_angle;
+                        let lobe_radius = radius * (0.4 + j as f64 * 0.1);
+
+                        let lobe_x = center_x + lobe_angle.cos() * lobe_radius;
+                        let lobe_y = center_y + lobe_angle.sin() * lobe_radius;
+
+                        renderer.begin_path();
+                        renderer.ellipse(
+                            lobe_x, lobe_y,
+                            radius * 0.12, radius * 0.08,
+                            lobe_angle,
+                            0.0, 2.0 * std::f64::consts::PI
+                        );
+                        renderer.stroke();
+                    }
+                }
+            },
+
+            _ => {}
+        }
+
+        // Reset line dash
+        renderer.set_line_dash(&[]);
+    }
+
+    fn draw_quarks(&self, renderer: &mut dyn Renderer, x: f64, y: f64, radius: f64, quark_types: &[&str]) {
+        const PI: f64 = std::f64::consts::PI;
+        let num_quarks = quark_types.len() as f64;
+
+        for (i, quark_type) in quark_types.iter().enumerate() {
+            let angle = (i as f64 * 2.0 * PI) / num_quarks;
+            let qx = x + angle.cos() * (radius * 0.5);
+            let qy = y + angle.sin() * (radius * 0.5);
+
+            renderer.begin_path();
+            renderer.arc(qx, qy, 5.0, 0.0, 2.0 * PI);
+
+            let fill_color = if *quark_type == "up" { "#ff6b6b" } else { "#4ecdc4" };
+            renderer.set_fill_style(fill_color);
+            renderer.fill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::test_support::{DrawCall, RecordingRenderer};
+
+    fn draw(simulation: &Simulation) -> RecordingRenderer {
+        let mut renderer = RecordingRenderer::default();
+        simulation.render(&mut renderer);
+        renderer
+    }
+
+    #[test]
+    fn empty_scene_draws_nothing() {
+        assert!(draw(&Simulation::new(800.0, 600.0)).calls.is_empty());
+    }
+
+    #[test]
+    fn free_neutron_is_a_white_circle_with_three_quarks() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_particle(100.0, 200.0, ParticleType::Neutron);
+        let renderer = draw(&simulation);
+
+        let body = DrawCall::Arc { x: 100.0, y: 200.0, radius: 20.0, start_angle: 0.0, end_angle: 2.0 * std::f64::consts::PI };
+        let position = renderer.calls.iter().position(|call| *call == body).expect("cuerpo del neutrón");
+        assert_eq!(
+            &renderer.calls[position + 1..position + 4],
+            &[DrawCall::StrokeStyle("#ffffff".into()), DrawCall::LineWidth(2.0), DrawCall::Stroke]
+        );
+
+        // Quarks up, down, down: un arco de radio 5 relleno por cada uno
+        let quarks = renderer.styles_at(|call| matches!(call, DrawCall::Fill));
+        assert_eq!(quarks, vec![(Some("#ff6b6b"), Some("#ffffff")), (Some("#4ecdc4"), Some("#ffffff")), (Some("#4ecdc4"), Some("#ffffff"))]);
+        assert!(renderer.texts().is_empty());
+    }
+
+    #[test]
+    fn lone_proton_is_labelled_as_hydrogen_ion() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_particle(400.0, 300.0, ParticleType::Proton);
+        let renderer = draw(&simulation);
+
+        assert_eq!(renderer.texts(), vec!["H+"]);
+        assert!(renderer.calls.contains(&DrawCall::FillText { text: "H+".into(), x: 400.0, y: 300.0 }));
+        // Dentro de un núcleo el protón se dibuja casi transparente y sin quarks
+        assert!(renderer.calls.contains(&DrawCall::StrokeStyle("rgba(255, 0, 0, 0.05)".into())));
+        assert!(!renderer.calls.contains(&DrawCall::FillStyle("#ff6b6b".into())));
+    }
+
+    #[test]
+    fn neutral_hydrogen_draws_its_1s_shell() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_nucleus(400.0, 300.0, 1, 0, 1);
+        simulation.update();
+        let renderer = draw(&simulation);
+
+        assert!(renderer.texts().contains(&"H"));
+        assert!(renderer.texts().contains(&"1s(1e⁻)"));
+        // El electrón ligado se rellena con el color de su orbital
+        assert!(renderer.calls.contains(&DrawCall::FillStyle("#ff9800".into())));
+    }
+
+    #[test]
+    fn photon_leaves_a_trail_along_its_motion() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_photon(200.0, 200.0, 0.0, 1.89); // Rojo (~656 nm), hacia +x
+        let renderer = draw(&simulation);
+
+        let trail = renderer.calls.iter().find_map(|call| match call {
+            DrawCall::Ellipse { x, y, rotation, .. } => Some((*x, *y, *rotation)),
+            _ => None,
+        });
+        let (x, y, rotation) = trail.expect("estela del fotón");
+        assert!(x < 200.0);
+        assert_eq!((y, rotation), (200.0, 0.0));
+    }
+
+    #[test]
+    fn covalent_bond_draws_a_segment_and_the_formula() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.params.bonding_enabled = true;
+        simulation.add_particle(300.0, 300.0, ParticleType::Proton);
+        simulation.add_particle(350.0, 300.0, ParticleType::Electron);
+        simulation.add_particle(420.0, 300.0, ParticleType::Proton);
+        simulation.add_particle(470.0, 300.0, ParticleType::Electron);
+        for _ in 0..5 {
+            simulation.update();
+        }
+        assert!(!simulation.bonds.is_empty());
+        let renderer = draw(&simulation);
+
+        let segments = renderer.calls.iter().filter(|call| matches!(call, DrawCall::LineTo { .. })).count();
+        assert_eq!(segments, simulation.bonds.iter().map(|bond| bond.order).sum::<usize>());
+        assert!(renderer.texts().contains(&"H2"));
+    }
+}
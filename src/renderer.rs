@@ -0,0 +1,176 @@
+// Interfaz de dibujo usada por `Simulation::render`.
+//
+// Sigue el modelo de trazos de Canvas2D (begin_path → arc/ellipse → stroke/fill)
+// para que cada backend (Canvas2D, SVG, pruebas...) solo tenga que traducir
+// estas primitivas.
+pub trait Renderer {
+    fn begin_path(&mut self);
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64);
+
+    #[allow(clippy::too_many_arguments)]
+    fn ellipse(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius_x: f64,
+        radius_y: f64,
+        rotation: f64,
+        start_angle: f64,
+        end_angle: f64,
+    );
+
+    // Rectángulo como subtrazo cerrado del trazo actual
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64);
+
+    // Segmentos rectos: empieza un subtrazo en (x, y) / lo prolonga hasta (x, y)
+    fn move_to(&mut self, x: f64, y: f64);
+
+    fn line_to(&mut self, x: f64, y: f64);
+
+    fn stroke(&mut self);
+
+    fn fill(&mut self);
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64);
+
+    fn set_fill_style(&mut self, color: &str);
+
+    fn set_stroke_style(&mut self, color: &str);
+
+    fn set_line_width(&mut self, width: f64);
+
+    // Segmentos de trazo/hueco; un slice vacío vuelve a línea continua
+    fn set_line_dash(&mut self, segments: &[f64]);
+
+    fn set_font(&mut self, font: &str);
+
+    fn set_text_align(&mut self, align: &str);
+
+    fn set_text_baseline(&mut self, baseline: &str);
+}
+
+// Backend de pruebas: guarda cada llamada en orden para comprobar qué se dibuja sin navegador
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::Renderer;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum DrawCall {
+        BeginPath,
+        Arc { x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64 },
+        Ellipse { x: f64, y: f64, radius_x: f64, radius_y: f64, rotation: f64 },
+        Rect { x: f64, y: f64, width: f64, height: f64 },
+        MoveTo { x: f64, y: f64 },
+        LineTo { x: f64, y: f64 },
+        Stroke,
+        Fill,
+        FillText { text: String, x: f64, y: f64 },
+        FillStyle(String),
+        StrokeStyle(String),
+        LineWidth(f64),
+        LineDash(Vec<f64>),
+        Font(String),
+        TextAlign(String),
+        TextBaseline(String),
+    }
+
+    #[derive(Debug, Default)]
+    pub(crate) struct RecordingRenderer {
+        pub calls: Vec<DrawCall>,
+    }
+
+    impl RecordingRenderer {
+        // Textos dibujados, en orden
+        pub fn texts(&self) -> Vec<&str> {
+            self.calls
+                .iter()
+                .filter_map(|call| match call {
+                    DrawCall::FillText { text, .. } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        // Estilo de relleno o trazo vigente en cada llamada que cumple `matches`
+        pub fn styles_at(&self, matches: impl Fn(&DrawCall) -> bool) -> Vec<(Option<&str>, Option<&str>)> {
+            let (mut fill, mut stroke) = (None, None);
+            let mut styles = Vec::new();
+            for call in &self.calls {
+                match call {
+                    DrawCall::FillStyle(color) => fill = Some(color.as_str()),
+                    DrawCall::StrokeStyle(color) => stroke = Some(color.as_str()),
+                    _ if matches(call) => styles.push((fill, stroke)),
+                    _ => {}
+                }
+            }
+            styles
+        }
+    }
+
+    impl Renderer for RecordingRenderer {
+        fn begin_path(&mut self) {
+            self.calls.push(DrawCall::BeginPath);
+        }
+
+        fn arc(&mut self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+            self.calls.push(DrawCall::Arc { x, y, radius, start_angle, end_angle });
+        }
+
+        fn ellipse(&mut self, x: f64, y: f64, radius_x: f64, radius_y: f64, rotation: f64, _start_angle: f64, _end_angle: f64) {
+            self.calls.push(DrawCall::Ellipse { x, y, radius_x, radius_y, rotation });
+        }
+
+        fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+            self.calls.push(DrawCall::Rect { x, y, width, height });
+        }
+
+        fn move_to(&mut self, x: f64, y: f64) {
+            self.calls.push(DrawCall::MoveTo { x, y });
+        }
+
+        fn line_to(&mut self, x: f64, y: f64) {
+            self.calls.push(DrawCall::LineTo { x, y });
+        }
+
+        fn stroke(&mut self) {
+            self.calls.push(DrawCall::Stroke);
+        }
+
+        fn fill(&mut self) {
+            self.calls.push(DrawCall::Fill);
+        }
+
+        fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+            self.calls.push(DrawCall::FillText { text: text.to_string(), x, y });
+        }
+
+        fn set_fill_style(&mut self, color: &str) {
+            self.calls.push(DrawCall::FillStyle(color.to_string()));
+        }
+
+        fn set_stroke_style(&mut self, color: &str) {
+            self.calls.push(DrawCall::StrokeStyle(color.to_string()));
+        }
+
+        fn set_line_width(&mut self, width: f64) {
+            self.calls.push(DrawCall::LineWidth(width));
+        }
+
+        fn set_line_dash(&mut self, segments: &[f64]) {
+            self.calls.push(DrawCall::LineDash(segments.to_vec()));
+        }
+
+        fn set_font(&mut self, font: &str) {
+            self.calls.push(DrawCall::Font(font.to_string()));
+        }
+
+        fn set_text_align(&mut self, align: &str) {
+            self.calls.push(DrawCall::TextAlign(align.to_string()));
+        }
+
+        fn set_text_baseline(&mut self, baseline: &str) {
+            self.calls.push(DrawCall::TextBaseline(baseline.to_string()));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+// Generador pseudoaleatorio SplitMix64: determinista y serializable, para que
+// una simulación guardada y restaurada siga exactamente el mismo camino
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+const DEFAULT_SEED: u64 = 0x5EED_A70B;
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniforme en [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Normal estándar (Box-Muller)
+    pub fn next_gaussian(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64(); // En (0, 1] para evitar ln(0)
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    // Ángulo uniforme en [0, 2π)
+    pub fn next_angle(&mut self) -> f64 {
+        self.next_f64() * 2.0 * std::f64::consts::PI
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Rng::new(DEFAULT_SEED)
+    }
+}
@@ -1,7 +1,24 @@
-use crate::particle::{Particle, ParticleType};
+use crate::particle::{Particle, ParticleId, ParticleType};
 use crate::physics::{Physics, PhysicsParams};
-use web_sys::CanvasRenderingContext2d;
-use wasm_bindgen::prelude::*;
+use crate::barnes_hut::QuadTree;
+use crate::boundary::BoundaryMode;
+use crate::chemistry::Bond;
+use crate::events::SimulationEvent;
+use crate::fission::HeldNucleus;
+use crate::photon::DEFAULT_PHOTON_ENERGY;
+use crate::reactor::Reactor;
+use crate::rng::Rng;
+use crate::snapshot::{self, SnapshotError};
+use crate::spatial::SpatialGrid;
+use serde::{Deserialize, Serialize};
+
+// Radios de captura/agrupamiento (también definen el tamaño de celda de la rejilla)
+pub(crate) const PN_ORBIT_RANGE: f64 = 60.0;
+pub(crate) const ELECTRON_CAPTURE_RANGE: f64 = 80.0;
+pub(crate) const NUCLEUS_CLUSTER_RANGE: f64 = 100.0;
+
+// Radio del anillo de protones al construir un núcleo (los neutrones van a 1.2×)
+pub(crate) const NUCLEUS_LAYOUT_RADIUS: f64 = 40.0;
 
 #[derive(Debug, Clone)]
 pub struct Nucleus {
@@ -10,12 +27,27 @@ pub struct Nucleus {
     pub electrons: Vec<usize>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Simulation {
-    pub particles: Vec<Particle>,
+    pub particles: Vec<Particle>, // Siempre ordenadas por id (las nuevas se añaden al final)
     pub canvas_width: f64,
     pub canvas_height: f64,
     pub params: PhysicsParams,
     pub time_step: f64,  // Para movimiento continuo de electrones
+    #[serde(default)]
+    next_id: ParticleId,
+    #[serde(default)]
+    pub(crate) rng: Rng,  // Procesos estocásticos (desintegraciones...)
+    #[serde(skip)]
+    pub(crate) events: Vec<SimulationEvent>,
+    #[serde(default)]
+    pub reactor: Option<Reactor>,  // Escenario de reactor (None = desactivado)
+    #[serde(default)]
+    pub bonds: Vec<Bond>,  // Enlaces químicos entre átomos
+    #[serde(default)]
+    pub boundary: BoundaryMode,  // Comportamiento en los bordes del lienzo
+    #[serde(default)]
+    pub(crate) held_nuclei: Vec<HeldNucleus>,  // Núcleos que se mueven en bloque (fisión, enlaces)
 }
 
 impl Simulation {
@@ -26,26 +58,80 @@ impl Simulation {
             canvas_height,
             params: PhysicsParams::default(),
             time_step: 0.0,
+            next_id: 0,
+            rng: Rng::default(),
+            events: Vec::new(),
+            reactor: None,
+            bonds: Vec::new(),
+            boundary: BoundaryMode::default(),
+            held_nuclei: Vec::new(),
         }
     }
 
-    pub fn add_particle(&mut self, x: f64, y: f64, ptype: ParticleType) {
-        let mass = match ptype {
-            ParticleType::Proton | ParticleType::Neutron => self.params.nuclear_mass,
-            ParticleType::Electron => self.params.electron_mass,
-        };
-        self.particles.push(Particle::new(x, y, ptype, mass));
+    // Reinicia el generador aleatorio (misma semilla = misma evolución)
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    // Sucesos ocurridos desde la última llamada
+    pub fn take_events(&mut self) -> Vec<SimulationEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Inserta una partícula asignándole un id nuevo
+    pub fn push_particle(&mut self, mut particle: Particle) -> ParticleId {
+        let id = self.next_id;
+        self.next_id += 1;
+        particle.id = id;
+        self.particles.push(particle);
+        id
+    }
+
+    // Índice actual en `particles` de la partícula con ese id
+    pub fn index_of(&self, id: ParticleId) -> Option<usize> {
+        debug_assert!(self.ids_sorted(), "particles must stay sorted by id");
+        self.particles.binary_search_by_key(&id, |p| p.id).ok()
+    }
+
+    // Invariante de la que depende `index_of`: ids estrictamente crecientes
+    fn ids_sorted(&self) -> bool {
+        self.particles.windows(2).all(|pair| pair[0].id < pair[1].id)
+    }
+
+    pub fn get_particle(&self, id: ParticleId) -> Option<&Particle> {
+        self.index_of(id).map(|idx| &self.particles[idx])
     }
 
-    pub fn add_particle_with_velocity(&mut self, x: f64, y: f64, ptype: ParticleType, dx: f64, dy: f64) {
+    // Elimina una partícula; las que orbitaban a su alrededor quedan libres
+    pub fn remove_particle(&mut self, id: ParticleId) -> Option<Particle> {
+        let idx = self.index_of(id)?;
+        self.break_bonds_of(id);
+        let removed = self.particles.remove(idx);
+        self.release_nucleons(&[id]);
+        for particle in &mut self.particles {
+            if particle.orbiting_around == Some(id) {
+                particle.orbiting_around = None;
+            }
+        }
+        Some(removed)
+    }
+
+    // Un fotón siempre viaja a `PHOTON_SPEED`: sin velocidad, sale hacia +x con `DEFAULT_PHOTON_ENERGY`
+    pub fn add_particle(&mut self, x: f64, y: f64, ptype: ParticleType) -> ParticleId {
+        self.add_particle_with_velocity(x, y, ptype, 0.0, 0.0)
+    }
+
+    // Para un fotón, (dx, dy) solo fija la dirección
+    pub fn add_particle_with_velocity(&mut self, x: f64, y: f64, ptype: ParticleType, dx: f64, dy: f64) -> ParticleId {
         let mass = match ptype {
             ParticleType::Proton | ParticleType::Neutron => self.params.nuclear_mass,
-            ParticleType::Electron => self.params.electron_mass,
+            ParticleType::Electron | ParticleType::Positron => self.params.electron_mass,
+            ParticleType::Photon => return self.add_photon(x, y, dy.atan2(dx), DEFAULT_PHOTON_ENERGY),
         };
         let mut particle = Particle::new(x, y, ptype, mass);
         particle.dx = dx;
         particle.dy = dy;
-        self.particles.push(particle);
+        self.push_particle(particle)
     }
 
     pub fn add_nucleus(
@@ -59,6 +145,12 @@ impl Simulation {
         self.add_nucleus_with_charge(x, y, protons, neutrons, electrons, 0)
     }
 
+    // Posición `i` de `count` repartidas en un anillo de radio `radius` centrado en (x, y)
+    pub(crate) fn ring_position(x: f64, y: f64, i: usize, count: usize, radius: f64) -> (f64, f64) {
+        let angle = (i as f64 * 2.0 * std::f64::consts::PI) / count.max(1) as f64;
+        (x + angle.cos() * radius, y + angle.sin() * radius)
+    }
+
     pub fn add_nucleus_with_charge(
         &mut self,
         x: f64,
@@ -66,26 +158,23 @@ impl Simulation {
         protons: usize,
         neutrons: usize,
         electrons: usize,
-        charge: i32, // Carga iónica: 0=neutro, >0=catión (+), <0=anión (-)
+        _charge: i32, // Carga iónica: 0=neutro, >0=catión (+), <0=anión (-)
     ) {
-        let radius = 40.0;
+        let radius = NUCLEUS_LAYOUT_RADIUS;
 
-        // Guardar índice donde comenzarán los protones
-        let proton_start_index = self.particles.len();
+        // Guardar el id del primer protón (los electrones orbitarán a su alrededor)
+        let mut first_proton_id = None;
 
         // Añadir protones
         for i in 0..protons {
-            let angle = (i as f64 * 2.0 * std::f64::consts::PI) / protons as f64;
-            let px = x + angle.cos() * radius;
-            let py = y + angle.sin() * radius;
-            self.add_particle(px, py, ParticleType::Proton);
+            let (px, py) = Self::ring_position(x, y, i, protons, radius);
+            let id = self.add_particle(px, py, ParticleType::Proton);
+            first_proton_id.get_or_insert(id);
         }
 
         // Añadir neutrones
         for i in 0..neutrons {
-            let angle = (i as f64 * 2.0 * std::f64::consts::PI) / neutrons.max(1) as f64;
-            let px = x + angle.cos() * (radius * 1.2);
-            let py = y + angle.sin() * (radius * 1.2);
+            let (px, py) = Self::ring_position(x, y, i, neutrons, radius * 1.2);
             self.add_particle(px, py, ParticleType::Neutron);
         }
 
@@ -110,21 +199,82 @@ impl Simulation {
                 electron.orbital_position = Some((subnivel, principal_n, orbital_idx, pos_in_orbital));
 
                 // Asociar electrón con el primer protón del núcleo
-                if protons > 0 {
-                    electron.orbiting_around = Some(proton_start_index);
-                }
+                electron.orbiting_around = first_proton_id;
 
-                self.particles.push(electron);
+                self.push_particle(electron);
             }
         }
     }
 
+    // Estado completo (partículas, enlaces orbitales, parámetros y reloj) como JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut simulation: Simulation = serde_json::from_str(json)?;
+        let particles = std::mem::take(&mut simulation.particles);
+        let next_id = simulation.next_id;
+        simulation.restore_particles(particles, next_id);
+        Ok(simulation)
+    }
+
+    // Snapshot binario compacto y versionado (ver `snapshot.rs`)
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        snapshot::encode(self)
+    }
+
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        snapshot::decode(bytes)
+    }
+
+    pub fn next_id(&self) -> ParticleId {
+        self.next_id
+    }
+
+    // Sustituye las partículas restaurando los invariantes de los ids
+    // (orden por id sin duplicados y ningún id futuro repetido), aunque vengan de un archivo editado a mano
+    pub(crate) fn restore_particles(&mut self, mut particles: Vec<Particle>, next_id: ParticleId) {
+        particles.sort_by_key(|p| p.id);
+        particles.dedup_by_key(|p| p.id);
+        let next_free = particles.last().map_or(0, |p| p.id + 1);
+        self.particles = particles;
+        self.next_id = next_id.max(next_free);
+        debug_assert!(self.ids_sorted());
+    }
+
     pub fn clear(&mut self) {
         self.particles.clear();
+        self.events.clear();
+        self.held_nuclei.clear();
+        self.bonds.clear();
+        self.reactor = None;
+    }
+
+    // Electrones que orbitan alguno de `protons`, en orden de id
+    pub(crate) fn electrons_bound_to(&self, protons: &[ParticleId]) -> Vec<ParticleId> {
+        let protons: std::collections::HashSet<ParticleId> = protons.iter().copied().collect();
+        self.particles
+            .iter()
+            .filter(|p| p.ptype == ParticleType::Electron && p.orbiting_around.is_some_and(|id| protons.contains(&id)))
+            .map(|p| p.id)
+            .collect()
+    }
+
+    // Liga los electrones a `proton` rehaciendo su configuración orbital en orden de llenado
+    pub(crate) fn bind_electrons(&mut self, electrons: &[ParticleId], proton: ParticleId) {
+        for (count, &id) in electrons.iter().enumerate() {
+            let orbital = self.get_orbital_info(count);
+            if let Some(idx) = self.index_of(id) {
+                let electron = &mut self.particles[idx];
+                electron.orbiting_around = Some(proton);
+                electron.orbital_position = orbital;
+            }
+        }
     }
 
     // Estructura orbital cuántica con subniveles y orbitales individuales
-    fn get_orbital_info(&self, electron_index: usize) -> Option<(char, usize, usize, usize)> {
+    pub(crate) fn get_orbital_info(&self, electron_index: usize) -> Option<(char, usize, usize, usize)> {
         // Retorna: (subnivel_tipo, nivel_principal, orbital_idx, posicion_en_orbital)
 
         let mut current_electron = 0;
@@ -219,12 +369,18 @@ impl Simulation {
             let pos_in_orbital = (electron_index - current_electron) % 2;
             return Some(('f', 4, orbital_idx, pos_in_orbital));
         }
-        current_electron += 14;
 
         // Continuar con más niveles si es necesario...
         None // Límite de electrones soportados (hasta 4f)
     }
 
+    // Posición en el orden de llenado de un orbital asignado por `get_orbital_info`
+    pub(crate) fn orbital_rank(&self, orbital: (char, usize, usize, usize)) -> usize {
+        (0..).take_while(|&i| self.get_orbital_info(i).is_some())
+            .find(|&i| self.get_orbital_info(i) == Some(orbital))
+            .unwrap_or(usize::MAX)
+    }
+
     // Obtener el número de orbitales por subnivel
     fn get_orbitals_count(&self, subnivel_tipo: char) -> usize {
         match subnivel_tipo {
@@ -236,17 +392,6 @@ impl Simulation {
         }
     }
 
-    // Obtener el máximo de electrones por subnivel
-    fn get_max_electrons_sublevel(&self, subnivel_tipo: char) -> usize {
-        match subnivel_tipo {
-            's' => 2,   // 2 electrones
-            'p' => 6,   // 6 electrones
-            'd' => 10,  // 10 electrones
-            'f' => 14,  // 14 electrones
-            _ => 2,
-        }
-    }
-
     // Obtener ángulo para orbitales individuales dentro de un subnivel
     fn get_orbital_angle(&self, subnivel_tipo: char, orbital_idx: usize) -> f64 {
         let orbitals_count = self.get_orbitals_count(subnivel_tipo);
@@ -320,66 +465,151 @@ impl Simulation {
 
     pub fn update(&mut self) {
         // Incrementar tiempo continuo para movimiento orbital
-        self.time_step += 0.05;
+        self.time_step += 0.05 * self.params.dt;
+
+        // Niveles de partida, para detectar los electrones que bajan durante el paso
+        let levels = self.params.radiation_enabled.then(|| self.electron_levels());
+
+        // Núcleos que se mueven en bloque (fisión, enlaces) según las funciones activas
+        self.refresh_held_nuclei();
+
+        // Núcleos de partida: tras mover las partículas, dos núcleos que chocan ya se detectan como uno
+        let previous_nuclei = self.params.fusion_enabled.then(|| self.nucleus_ids());
+
+        // Muelles de los enlaces químicos, con los átomos tal como están al empezar el paso
+        let springs = if self.params.bonding_enabled { self.bond_springs() } else { Vec::new() };
+
+        // Avanzar posiciones y velocidades con el integrador seleccionado
+        let mut particles = std::mem::take(&mut self.particles);
+        self.params.integrator.step(&mut particles, self.params.dt, |state| {
+            let mut forces = self.calculate_forces(state);
+            Self::add_bond_forces(state, &springs, &mut forces);
+            forces
+        });
+        self.particles = particles;
+        self.apply_held_nuclei();
 
-        self.calculate_forces();
+        // Antes de las órbitas: un neutrón que acaba de entrar aún no está ligado a ningún protón
+        if self.params.fission_enabled {
+            self.handle_fissions();
+        }
+        // Moderadores, barras de control y combustible del escenario de reactor (si lo hay)
+        self.handle_reactor();
 
-        // Detectar y manejar fusiones antes de actualizar posiciones
+        // Órbitas: fijan posición y velocidad de las partículas ligadas
         self.handle_fusions_and_orbits();
 
+        if let Some(previous) = previous_nuclei {
+            self.handle_fusions(&previous);
+        }
+        if self.params.decay_enabled {
+            self.handle_decays();
+        }
+        // Tras las órbitas: los electrones ligados ya están en su posición de este paso
+        if self.params.ionization_enabled {
+            self.handle_ionizations();
+        }
+        if let Some(levels) = levels {
+            self.handle_photons();
+            self.emit_transition_photons(&levels);
+        }
+        if self.params.electron_transfer_enabled {
+            self.handle_electron_transfers();
+        }
+        // Con la carga de cada átomo ya decidida en este paso
+        if self.params.bonding_enabled {
+            self.handle_bonds();
+        }
+
+        // Tras todos los procesos del paso: solo actúa sobre las partículas que siguen libres
+        if self.params.thermostat_enabled {
+            self.apply_thermostat();
+        }
+
         for particle in &mut self.particles {
             particle.update(
                 self.canvas_width,
                 self.canvas_height,
+                self.boundary,
                 if particle.ptype == ParticleType::Electron {
                     self.params.electron_damping
                 } else {
                     self.params.nuclear_damping
                 },
+                self.params.dt,
             );
         }
+
+        // Los fotones que salen del lienzo se pierden (salvo con contorno periódico, que los
+        // devuelve por el borde opuesto); con contorno absorbente, también las partículas libres
+        let (width, height) = (self.canvas_width, self.canvas_height);
+        let outside = |p: &Particle| p.x < 0.0 || p.x > width || p.y < 0.0 || p.y > height;
+        let lost: Vec<ParticleId> = self
+            .particles
+            .iter()
+            .filter(|p| match self.boundary {
+                BoundaryMode::Periodic => false,
+                BoundaryMode::Absorbing => p.orbiting_around.is_none() && outside(p),
+                _ => p.ptype == ParticleType::Photon && outside(p),
+            })
+            .map(|p| p.id)
+            .collect();
+        for id in lost {
+            self.remove_particle(id);
+        }
     }
 
     fn handle_fusions_and_orbits(&mut self) {
         let particles_count = self.particles.len();
-        let mut to_remove: std::collections::HashSet<usize> = std::collections::HashSet::new();
 
-        // PASO 1: Detectar PN y EN órbitas (sin fusión)
-        for i in 0..particles_count {
-            if to_remove.contains(&i) {
-                continue;
+        // Fase amplia: solo se visitan pares dentro del mayor radio de captura
+        let capture_range = PN_ORBIT_RANGE.max(ELECTRON_CAPTURE_RANGE);
+        let grid = SpatialGrid::new(&self.particles, capture_range);
+        let held: std::collections::HashSet<ParticleId> =
+            self.held_nuclei.iter().flat_map(|n| n.nucleons.iter().map(|&(id, _, _)| id)).collect();
+
+        // Electrones ya ligados a cada protón (antes se recontaban en cada par)
+        let mut electron_counts: Vec<usize> = vec![0; particles_count];
+        for particle in &self.particles {
+            if particle.ptype == ParticleType::Electron {
+                if let Some(proton_idx) = particle.orbiting_around.and_then(|id| self.index_of(id)) {
+                    electron_counts[proton_idx] += 1;
+                }
             }
+        }
 
+        // PASO 1: Detectar PN y EN órbitas (sin fusión)
+        for i in 0..particles_count {
             let particle_i = &self.particles[i];
             let pi_type = particle_i.ptype;
             let pi_x = particle_i.x;
             let pi_y = particle_i.y;
 
-            for j in i + 1..particles_count {
-                if to_remove.contains(&j) {
+            for j in self.neighbors_within(&grid, pi_x, pi_y, capture_range) {
+                if j <= i {
                     continue;
                 }
 
                 let particle_j = &self.particles[j];
                 let pj_type = particle_j.ptype;
-                let pj_x = particle_j.x;
-                let pj_y = particle_j.y;
 
-                let dx = pj_x - pi_x;
-                let dy = pj_y - pi_y;
+                let (dx, dy) = self.separation(&self.particles[i], particle_j);
                 let distance = (dx * dx + dy * dy).sqrt();
 
                 // ÓRBITA P-N: Protón + Neutrón orbitan entre sí (SIN fusión)
-                if (pi_type == ParticleType::Proton && pj_type == ParticleType::Neutron)
-                    || (pi_type == ParticleType::Neutron && pj_type == ParticleType::Proton)
+                if ((pi_type == ParticleType::Proton && pj_type == ParticleType::Neutron)
+                    || (pi_type == ParticleType::Neutron && pj_type == ParticleType::Proton))
+                    && distance < PN_ORBIT_RANGE
+                    && distance > 15.0
                 {
-                    if distance < 60.0 && distance > 15.0 {
-                        let (heavier_idx, lighter_idx) = if pi_type == ParticleType::Proton {
-                            (i, j)
-                        } else {
-                            (j, i)
-                        };
-                        self.particles[lighter_idx].orbiting_around = Some(heavier_idx);
+                    let (heavier_idx, lighter_idx) = if pi_type == ParticleType::Proton {
+                        (i, j)
+                    } else {
+                        (j, i)
+                    };
+                    // Los neutrones de un núcleo retenido no orbitan: conservan su sitio en él
+                    if !held.contains(&self.particles[lighter_idx].id) {
+                        self.particles[lighter_idx].orbiting_around = Some(self.particles[heavier_idx].id);
                     }
                 }
 
@@ -396,23 +626,23 @@ impl Simulation {
                     // Solo capturar si el electrón no está ya orbitando y tiene orbital_position asignado
                     if self.particles[electron_idx].orbiting_around.is_none() &&
                        self.particles[electron_idx].orbital_position.is_some() &&
-                       distance < 80.0 && distance > 10.0 {
+                       distance < ELECTRON_CAPTURE_RANGE && distance > 10.0 {
 
                         // Si el electrón ya tiene orbital_position (creado desde add_nucleus), solo asociarlo
-                        self.particles[electron_idx].orbiting_around = Some(proton_idx);
+                        self.particles[electron_idx].orbiting_around = Some(self.particles[proton_idx].id);
+                        electron_counts[proton_idx] += 1;
 
                     } else if self.particles[electron_idx].orbiting_around.is_none() &&
                               self.particles[electron_idx].orbital_position.is_none() &&
-                              distance < 80.0 && distance > 10.0 {
+                              distance < ELECTRON_CAPTURE_RANGE && distance > 10.0 {
 
                         // Electrón libre: asignar nuevo orbital
-                        let electron_count = self.particles.iter()
-                            .filter(|p| p.orbiting_around == Some(proton_idx) && p.ptype == ParticleType::Electron)
-                            .count();
+                        let electron_count = electron_counts[proton_idx];
 
                         if let Some((subnivel, principal_n, orbital_idx, pos_in_orbital)) = self.get_orbital_info(electron_count) {
-                            self.particles[electron_idx].orbiting_around = Some(proton_idx);
+                            self.particles[electron_idx].orbiting_around = Some(self.particles[proton_idx].id);
                             self.particles[electron_idx].orbital_position = Some((subnivel, principal_n, orbital_idx, pos_in_orbital));
+                            electron_counts[proton_idx] += 1;
                         }
                     }
                 }
@@ -421,15 +651,6 @@ impl Simulation {
 
         // PASO 2: Aplicar posiciones orbitales
         self.apply_orbital_mechanics();
-
-        // Remover partículas si es necesario (nada por ahora, ya no hay fusión P-N)
-        let mut indices: Vec<_> = to_remove.into_iter().collect();
-        indices.sort_by(|a, b| b.cmp(a));
-        for idx in indices {
-            if idx < self.particles.len() {
-                self.particles.remove(idx);
-            }
-        }
     }
 
     fn apply_orbital_mechanics(&mut self) {
@@ -441,53 +662,44 @@ impl Simulation {
 
             // Aplicar órbita P-N (neutrón orbita protón)
             if particle.ptype == ParticleType::Neutron {
-                if let Some(proton_idx) = particle.orbiting_around {
-                    if proton_idx < particles_count {
-                        let proton = &self.particles[proton_idx];
-                        let px = proton.x;
-                        let py = proton.y;
-                        let pdx = proton.dx;
-                        let pdy = proton.dy;
-
-                        let dx = px - particle.x;
-                        let dy = py - particle.y;
-                        let distance = (dx * dx + dy * dy).sqrt().max(1.0);
-
-                        // Órbita a 45 píxeles
-                        let orbital_radius = 45.0;
-                        let angle = dy.atan2(dx);
-                        let orbital_speed = 2.5;
-
-                        let new_x = px + orbital_radius * angle.cos();
-                        let new_y = py + orbital_radius * angle.sin();
-                        let new_dx = pdx - orbital_speed * angle.sin();
-                        let new_dy = pdy + orbital_speed * angle.cos();
-
-                        orbital_updates.push((i, new_x, new_y, new_dx, new_dy));
-                    }
+                if let Some(proton_idx) = particle.orbiting_around.and_then(|id| self.index_of(id)) {
+                    let proton = &self.particles[proton_idx];
+                    let px = proton.x;
+                    let py = proton.y;
+                    let pdx = proton.dx;
+                    let pdy = proton.dy;
+
+                    let (dx, dy) = self.separation(particle, proton);
+                    // Órbita a 45 píxeles
+                    let orbital_radius = 45.0;
+                    let angle = dy.atan2(dx);
+                    let orbital_speed = 2.5;
+
+                    let new_x = px + orbital_radius * angle.cos();
+                    let new_y = py + orbital_radius * angle.sin();
+                    let new_dx = pdx - orbital_speed * angle.sin();
+                    let new_dy = pdy + orbital_speed * angle.cos();
+
+                    orbital_updates.push((i, new_x, new_y, new_dx, new_dy));
                 }
             }
 
             // Aplicar movimiento orbital realista dentro de formas características
             // Solo para electrones que están en órbita y tienen asignada información orbital completa
             if particle.ptype == ParticleType::Electron {
-                if particle.orbiting_around.is_some() && particle.orbital_position.is_some() {
-                    if let Some((subnivel, principal_n, orbital_idx, pos_in_orbital)) = particle.orbital_position {
-                        if let Some(proton_idx) = particle.orbiting_around {
-                            if proton_idx < particles_count {
-                                let proton = &self.particles[proton_idx];
-                                let px = proton.x;
-                                let py = proton.y;
-
-                                // Movimiento orbital basado en el tiempo continuo y el tipo de subnivel
-                                let (new_x, new_y, new_dx, new_dy) = self.calculate_orbital_motion(
-                                    px, py, subnivel, principal_n, orbital_idx, pos_in_orbital, self.time_step
-                                );
-
-                                orbital_updates.push((i, new_x, new_y, new_dx, new_dy));
-                            }
-                        }
-                    }
+                if let (Some((subnivel, principal_n, orbital_idx, pos_in_orbital)), Some(proton_idx)) =
+                    (particle.orbital_position, particle.orbiting_around.and_then(|id| self.index_of(id)))
+                {
+                    let proton = &self.particles[proton_idx];
+                    let px = proton.x;
+                    let py = proton.y;
+
+                    // Movimiento orbital basado en el tiempo continuo y el tipo de subnivel
+                    let (new_x, new_y, new_dx, new_dy) = self.calculate_orbital_motion(
+                        px, py, subnivel, principal_n, orbital_idx, pos_in_orbital, self.time_step
+                    );
+
+                    orbital_updates.push((i, new_x, new_y, new_dx, new_dy));
                 }
             }
         }
@@ -503,70 +715,124 @@ impl Simulation {
         }
     }
 
-    fn calculate_forces(&mut self) {
-        let particles_count = self.particles.len();
+    // Vector de `a` a `b`; con contorno periódico, hacia la imagen más cercana de `b`
+    pub(crate) fn separation(&self, a: &Particle, b: &Particle) -> (f64, f64) {
+        self.boundary.minimum_image(b.x - a.x, b.y - a.y, self.canvas_width, self.canvas_height)
+    }
+
+    // Candidatos a vecino nuclear de `particle` (también a través de los bordes si son periódicos)
+    pub(crate) fn nuclear_neighbors(&self, grid: &SpatialGrid, particle: &Particle) -> Vec<usize> {
+        self.neighbors_within(grid, particle.x, particle.y, self.params.nuclear_range)
+    }
+
+    // Candidatos a menos de `range` de (x, y), también a través de los bordes si son periódicos
+    pub(crate) fn neighbors_within(&self, grid: &SpatialGrid, x: f64, y: f64, range: f64) -> Vec<usize> {
+        match self.boundary {
+            BoundaryMode::Periodic => grid.neighbors_periodic(x, y, range, self.canvas_width, self.canvas_height),
+            _ => grid.neighbors(x, y, range),
+        }
+    }
+
+    // Fuerza total sobre cada partícula para el estado `particles`
+    pub(crate) fn calculate_forces(&self, particles: &[Particle]) -> Vec<(f64, f64)> {
+        let particles_count = particles.len();
         let mut forces: Vec<(f64, f64)> = vec![(0.0, 0.0); particles_count];
 
-        for i in 0..particles_count {
-            for j in i + 1..particles_count {
-                let particle_i = &self.particles[i];
-                let particle_j = &self.particles[j];
+        let mut add_pair_force = |i: usize, j: usize, dx: f64, dy: f64, distance: f64, force_magnitude: f64| {
+            let force_x = (force_magnitude * dx) / distance;
+            let force_y = (force_magnitude * dy) / distance;
 
-                let dx = particle_j.x - particle_i.x;
-                let dy = particle_j.y - particle_i.y;
-                let distance = (dx * dx + dy * dy).sqrt().max(1.0);
+            forces[i].0 += force_x;
+            forces[i].1 += force_y;
+            forces[j].0 -= force_x;
+            forces[j].1 -= force_y;
+        };
 
-                let mut force_magnitude = 0.0;
+        // Corto alcance: fuerza nuclear entre nucleones vecinos (rejilla de lado nuclear_range)
+        let is_nucleon = |p: &Particle| p.ptype == ParticleType::Proton || p.ptype == ParticleType::Neutron;
+        let grid = SpatialGrid::new(particles, self.params.nuclear_range);
+        for i in 0..particles_count {
+            let particle_i = &particles[i];
+            if !is_nucleon(particle_i) {
+                continue;
+            }
 
-                // Fuerzas nucleares entre protones y neutrones
-                if (particle_i.ptype == ParticleType::Proton
-                    || particle_i.ptype == ParticleType::Neutron)
-                    && (particle_j.ptype == ParticleType::Proton
-                        || particle_j.ptype == ParticleType::Neutron)
-                {
-                    force_magnitude +=
-                        Physics::calculate_nuclear_force(distance, &self.params);
-
-                    // Repulsión entre protones
-                    if particle_i.ptype == ParticleType::Proton
-                        && particle_j.ptype == ParticleType::Proton
-                    {
-                        force_magnitude -= self.params.proton_repulsion / (distance + 0.1);
-                    }
+            for j in self.nuclear_neighbors(&grid, particle_i) {
+                let particle_j = &particles[j];
+                if j <= i || !is_nucleon(particle_j) {
+                    continue;
                 }
 
-                // Fuerzas de Coulomb (electrostáticas)
-                if particle_i.charge != 0.0 && particle_j.charge != 0.0 {
-                    let coulomb =
-                        Physics::calculate_coulomb_force(distance, particle_i.charge, particle_j.charge, &self.params);
-                    force_magnitude += coulomb;
-                }
+                let (dx, dy) = self.separation(particle_i, particle_j);
+                let distance = (dx * dx + dy * dy).sqrt().max(1.0);
 
-                // Repulsión entre electrones
-                if particle_i.ptype == ParticleType::Electron
-                    && particle_j.ptype == ParticleType::Electron
-                {
-                    force_magnitude -= self.params.electron_repulsion / (distance + 0.1);
+                let force_magnitude = Physics::calculate_nuclear_force(distance, &self.params);
+                if force_magnitude != 0.0 {
+                    add_pair_force(i, j, dx, dy, distance, force_magnitude);
                 }
+            }
+        }
 
-                let force_x = (force_magnitude * dx) / distance;
-                let force_y = (force_magnitude * dy) / distance;
+        // Largo alcance: solo intervienen partículas cargadas
+        let charged: Vec<usize> = (0..particles_count)
+            .filter(|&i| particles[i].charge != 0.0)
+            .collect();
+
+        // El quadtree no conoce las imágenes periódicas: con ese contorno se suma directamente
+        if self.params.barnes_hut && self.boundary != BoundaryMode::Periodic {
+            // Barnes-Hut: un quadtree por signo de carga para que cada celda tenga
+            // un centro de carga bien definido
+            let positive: Vec<usize> = charged.iter().copied().filter(|&i| particles[i].charge > 0.0).collect();
+            let negative: Vec<usize> = charged.iter().copied().filter(|&i| particles[i].charge < 0.0).collect();
+            let trees = [QuadTree::build(particles, &positive), QuadTree::build(particles, &negative)];
+
+            for &i in &charged {
+                for tree in &trees {
+                    let (fx, fy) = tree.force_on(particles, i, self.params.barnes_hut_theta, &self.params);
+                    forces[i].0 += fx;
+                    forces[i].1 += fy;
+                }
+            }
+        } else {
+            for (a, &i) in charged.iter().enumerate() {
+                for &j in &charged[a + 1..] {
+                    let particle_i = &particles[i];
+                    let particle_j = &particles[j];
+
+                    let (dx, dy) = self.separation(particle_i, particle_j);
+                    let distance = (dx * dx + dy * dy).sqrt().max(1.0);
+
+                    let force_magnitude = Physics::calculate_long_range_force(
+                        distance,
+                        particle_i,
+                        particle_j.charge,
+                        (particle_j.ptype == ParticleType::Proton) as usize,
+                        (particle_j.ptype == ParticleType::Electron) as usize,
+                        &self.params,
+                    );
 
-                forces[i].0 += force_x;
-                forces[i].1 += force_y;
-                forces[j].0 -= force_x;
-                forces[j].1 -= force_y;
+                    add_pair_force(i, j, dx, dy, distance, force_magnitude);
+                }
             }
         }
 
-        for i in 0..particles_count {
-            Physics::apply_force(&mut self.particles[i], forces[i].0, forces[i].1);
+        // Campos externos uniformes: fuerza de Lorentz con la velocidad del estado evaluado
+        let fields = self.params.electric_field_x != 0.0 || self.params.electric_field_y != 0.0 || self.params.magnetic_field != 0.0;
+        if fields {
+            for &i in &charged {
+                let (fx, fy) = Physics::calculate_lorentz_force(&particles[i], &self.params);
+                forces[i].0 += fx;
+                forces[i].1 += fy;
+            }
         }
+
+        forces
     }
 
     pub fn detect_nuclei(&self) -> Vec<Nucleus> {
         let mut nuclei = Vec::new();
         let mut used_particles = std::collections::HashSet::new();
+        let grid = SpatialGrid::new(&self.particles, NUCLEUS_CLUSTER_RANGE);
 
         // Detectar protones agrupados
         for (i, particle) in self.particles.iter().enumerate() {
@@ -582,13 +848,15 @@ impl Simulation {
             used_particles.insert(i);
 
             // Buscar partículas cercanas
-            for (j, other) in self.particles.iter().enumerate() {
+            for j in self.neighbors_within(&grid, particle.x, particle.y, NUCLEUS_CLUSTER_RANGE) {
                 if used_particles.contains(&j) || i == j {
                     continue;
                 }
 
-                let distance = particle.distance_to(other);
-                if distance < 100.0 {
+                let other = &self.particles[j];
+                let (dx, dy) = self.separation(particle, other);
+                let distance = dx.hypot(dy);
+                if distance < NUCLEUS_CLUSTER_RANGE {
                     match other.ptype {
                         ParticleType::Proton => {
                             nucleus.protons.push(j);
@@ -601,11 +869,12 @@ impl Simulation {
                         ParticleType::Electron => {
                             nucleus.electrons.push(j);
                         }
+                        ParticleType::Positron | ParticleType::Photon => {}
                     }
                 }
             }
 
-            if nucleus.protons.len() > 0 {
+            if !nucleus.protons.is_empty() {
                 nuclei.push(nucleus);
             }
         }
@@ -613,509 +882,8 @@ impl Simulation {
         nuclei
     }
 
-    pub fn render(&self, context: &CanvasRenderingContext2d) {
-
-        // Detectar núcleos y crear un conjunto de índices de partículas en núcleos
-        let nuclei = self.detect_nuclei();
-        let mut nucleus_particle_indices = std::collections::HashSet::new();
-
-        for nucleus in &nuclei {
-            for &proton_idx in &nucleus.protons {
-                nucleus_particle_indices.insert(proton_idx);
-            }
-            for &neutron_idx in &nucleus.neutrons {
-                nucleus_particle_indices.insert(neutron_idx);
-            }
-        }
-
-        // Dibujar núcleos detectados y orbitales cuánticos SOLO si hay electrones en ese nivel
-        for nucleus in &nuclei {
-            if let Some(proton_idx) = nucleus.protons.get(0) {
-                if let Some(_proton) = self.particles.get(*proton_idx) {
-                    // Calcular centro del núcleo
-                    let mut center_x = 0.0;
-                    let mut center_y = 0.0;
-                    let proton_count = nucleus.protons.len();
-
-                    for &p_idx in &nucleus.protons {
-                        if let Some(p) = self.particles.get(p_idx) {
-                            center_x += p.x;
-                            center_y += p.y;
-                        }
-                    }
-
-                    if proton_count > 0 {
-                        center_x /= proton_count as f64;
-                        center_y /= proton_count as f64;
-
-                        // Contar electrones por orbital según el nuevo sistema spdf
-                        let mut e_1s = 0;
-                        let mut e_2s = 0;
-                        let mut e_2p = 0;
-                        let mut e_3s = 0;
-                        let mut e_3p = 0;
-                        let mut e_4s = 0;
-                        let mut e_3d = 0;
-                        let mut e_4f = 0;
-
-                        for &e_idx in &nucleus.electrons {
-                            if let Some(e) = self.particles.get(e_idx) {
-                                if let Some((subnivel, principal_n, _orbital_idx, _pos_in_orbital)) = e.orbital_position {
-                                    match (principal_n, subnivel) {
-                                        (1, 's') => e_1s += 1,
-                                        (2, 's') => e_2s += 1,
-                                        (2, 'p') => e_2p += 1,
-                                        (3, 's') => e_3s += 1,
-                                        (3, 'p') => e_3p += 1,
-                                        (4, 's') => e_4s += 1,
-                                        (3, 'd') => e_3d += 1,
-                                        (4, 'f') => e_4f += 1,
-                                        _ => {}
-                                    }
-                                }
-                            }
-                        }
-
-                        // Visualización explícita de subniveles spdf con formas características
-                        let orbital_visualizations = [
-                            ("1s", e_1s, 60.0, "rgba(255,152,0,0.4)", "s"),     // naranja - esfera
-                            ("2s", e_2s, 90.0, "rgba(255,255,0,0.4)", "s"),    // amarillo - esfera más grande
-                            ("2p", e_2p, 120.0, "rgba(76,175,80,0.4)", "p"),   // verde - formas de huso
-                            ("3s", e_3s, 150.0, "rgba(156,39,176,0.4)", "s"),  // púrpura - esfera
-                            ("3p", e_3p, 180.0, "rgba(33,150,243,0.4)", "p"),  // azul - husos
-                            ("4s", e_4s, 210.0, "rgba(255,87,34,0.4)", "s"),   // naranja rojo - esfera
-                            ("3d", e_3d, 240.0, "rgba(142,36,170,0.4)", "d"),  // violeta - formas de trébol
-                            ("4f", e_4f, 280.0, "rgba(0,188,212,0.4)", "f"),   // cyan - formas complejas
-                        ];
-                        for (sublevel_name, e_count, radius, color, kind) in orbital_visualizations.iter() {
-                            if *e_count > 0 {
-                                // Visualizar formas características de cada subnivel
-                                self.draw_subnivel_shape(context, center_x, center_y, *radius, color, kind, *e_count);
-
-                                // Etiqueta del subnivel con número de electrones
-                                context.set_fill_style(&"#ffffff".into());
-                                context.set_font("bold 12px Arial");
-                                context.set_text_align("center");
-                                let label = format!("{}({}e⁻)", sublevel_name, e_count);
-                                let label_y = match *kind {
-                                    "s" => center_y + *radius + 20.0,
-                                    "p" => center_y + *radius + 40.0,
-                                    "d" => center_y + *radius + 60.0,
-                                    "f" => center_y + *radius + 80.0,
-                                    _ => center_y + *radius + 20.0,
-                                };
-                                let _ = context.fill_text(&label, center_x, label_y);
-                            }
-                        }
-
-                        // Dibujar círculo de fondo del elemento con color único y 50% transparencia
-                        self.draw_element_background(context, center_x, center_y, nucleus.protons.len());
-
-                        // Dibujar letra del elemento en el centro del núcleo
-                        self.draw_element_symbol(context, center_x, center_y, nucleus);
-                    }
-                }
-            }
-        }
-
-        // Dibujar partículas
-        for (idx, particle) in self.particles.iter().enumerate() {
-            let in_nucleus = nucleus_particle_indices.contains(&idx);
-
-            // Dibujar círculo principal
-            context.begin_path();
-            let draw_radius = if particle.ptype == ParticleType::Electron { particle.radius / 3.0 } else { particle.radius };
-            let _ = context.arc(
-                particle.x,
-                particle.y,
-                draw_radius,
-                0.0,
-                2.0 * std::f64::consts::PI,
-            );
-
-            match particle.ptype {
-                ParticleType::Proton => {
-                    let color = if in_nucleus {
-                        "rgba(255, 0, 0, 0.05)"  // 95% transparencia (5% opacidad)
-                    } else {
-                        "#ff0000"
-                    };
-                    let _ = context.set_stroke_style(&color.into());
-                    let _ = context.set_line_width(2.0);
-                    let _ = context.stroke();
-                    // Dibujar quarks (up, up, down) para protón
-                    if !in_nucleus {
-                        self.draw_quarks(context, particle.x, particle.y, particle.radius, &["up", "up", "down"]);
-                    }
-                }
-                ParticleType::Neutron => {
-                    let color = if in_nucleus {
-                        "rgba(255, 255, 255, 0.05)"  // 95% transparencia
-                    } else {
-                        "#ffffff"
-                    };
-                    let _ = context.set_stroke_style(&color.into());
-                    let _ = context.set_line_width(2.0);
-                    let _ = context.stroke();
-                    // Dibujar quarks (up, down, down) para neutrón
-                    if !in_nucleus {
-                        self.draw_quarks(context, particle.x, particle.y, particle.radius, &["up", "down", "down"]);
-                    }
-                }
-                ParticleType::Electron => {
-                    // Color según orbital cuántico
-                    let color = if let Some((subnivel, principal_n, _orbital_idx, _pos_in_orbital)) = particle.orbital_position {
-                        match (principal_n, subnivel) {
-                            (1, 's') => "#ff9800",  // 1s: naranja
-                            (2, 's') => "#ffff00",  // 2s: amarillo
-                            (2, 'p') => "#4caf50",  // 2p: verde
-                            (3, 's') => "#9c27b0",  // 3s: púrpura
-                            (3, 'p') => "#2196f3",  // 3p: azul
-                            (4, 's') => "#ff5722",  // 4s: naranja rojo
-                            (3, 'd') => "#8e24aa",  // 3d: violeta
-                            (4, 'f') => "#00bcd4",  // 4f: cyan
-                            (5, 's') => "#795548",  // 5s: café
-                            (4, 'd') => "#607d8b",  // 4d: azul gris
-                            (5, 'p') => "#e91e63",  // 5p: rosa
-                            (6, 's') => "#9e9e9e",  // 6s: gris
-                            _ => "#ffffff",
-                        }
-                    } else {
-                        "#ffffff" // Libre
-                    };
-                    let _ = context.set_fill_style(&color.into());
-                    let _ = context.fill();
-                }
-            }
-        }
-    }
-
-    fn get_element_symbol(proton_count: usize) -> &'static str {
-        match proton_count {
-            1 => "H",   // Hidrógeno
-            2 => "He",  // Helio
-            3 => "Li",  // Litio
-            4 => "Be",  // Berilio
-            5 => "B",   // Boro
-            6 => "C",   // Carbono
-            7 => "N",   // Nitrógeno
-            8 => "O",   // Oxígeno
-            9 => "F",   // Flúor
-            10 => "Ne",  // Neón
-            11 => "Na", // Sodio
-            12 => "Mg", // Magnesio
-            13 => "Al", // Aluminio
-            14 => "Si", // Silicio
-            15 => "P",  // Fósforo
-            16 => "S",  // Azufre
-            17 => "Cl", // Cloro
-            18 => "Ar", // Argón
-            19 => "K",  // Potasio
-            20 => "Ca", // Calcio
-            21 => "Sc", // Escandio
-            22 => "Ti", // Titanio
-            23 => "V",  // Vanadio
-            24 => "Cr", // Cromo
-            25 => "Mn", // Manganeso
-            26 => "Fe", // Hierro
-            27 => "Co", // Cobalto
-            28 => "Ni", // Níquel
-            29 => "Cu", // Cobre
-            30 => "Zn", // Zinc
-            31 => "Ga", // Galio
-            32 => "Ge", // Germanio
-            33 => "As", // Arsénico
-            34 => "Se", // Selenio
-            35 => "Br", // Bromo
-            36 => "Kr", // Kriptón
-            37 => "Rb", // Rubidio
-            38 => "Sr", // Estroncio
-            39 => "Y",  // Itrio
-            40 => "Zr", // Circonio
-            41 => "Nb", // Niobio
-            42 => "Mo", // Molibdeno
-            43 => "Tc", // Tecnecio
-            44 => "Ru", // Rutenio
-            45 => "Rh", // Rodio
-            46 => "Pd", // Paladio
-            47 => "Ag", // Plata
-            48 => "Cd", // Cadmio
-            49 => "In", // Indio
-            50 => "Sn", // Estaño
-            51 => "Sb", // Antimonio
-            52 => "Te", // Telurio
-            53 => "I",  // Yodo
-            54 => "Xe", // Xenón
-            55 => "Cs", // Cesio
-            56 => "Ba", // Bario
-            57 => "La", // Lantano
-            58 => "Ce", // Cerio
-            59 => "Pr", // Praseodimio
-            60 => "Nd", // Neodimio
-            61 => "Pm", // Prometio
-            62 => "Sm", // Samario
-            63 => "Eu", // Europio
-            64 => "Gd", // Gadolinio
-            65 => "Tb", // Terbio
-            66 => "Dy", // Disprosio
-            67 => "Ho", // Holmio
-            68 => "Er", // Erbio
-            69 => "Tm", // Tulio
-            70 => "Yb", // Iterbio
-            71 => "Lu", // Lutecio
-            72 => "Hf", // Hafnio
-            73 => "Ta", // Tántalo
-            74 => "W",  // Tungsteno
-            75 => "Re", // Renio
-            76 => "Os", // Osmio
-            77 => "Ir", // Iridio
-            78 => "Pt", // Platino
-            79 => "Au", // Oro
-            80 => "Hg", // Mercurio
-            81 => "Tl", // Talio
-            82 => "Pb", // Plomo
-            83 => "Bi", // Bismuto
-            84 => "Po", // Polonio
-            85 => "At", // Astato
-            86 => "Rn", // Radón
-            87 => "Fr", // Francio
-            88 => "Ra", // Radio
-            89 => "Ac", // Actinio
-            90 => "Th", // Torio
-            91 => "Pa", // Protactinio
-            92 => "U",  // Uranio
-            93 => "Np", // Neptunio
-            94 => "Pu", // Plutonio
-            95 => "Am", // Americio
-            96 => "Cm", // Curio
-            97 => "Bk", // Berkelio
-            98 => "Cf", // Californio
-            99 => "Es", // Einstenio
-            100 => "Fm", // Fermio
-            101 => "Md", // Mendelevio
-            102 => "No", // Nobelio
-            103 => "Lr", // Laurencio
-            104 => "Rf", // Rutherfordio
-            105 => "Db", // Dubnio
-            106 => "Sg", // Seaborgio
-            107 => "Bh", // Bohrio
-            108 => "Hs", // Hassio
-            109 => "Mt", // Meitnerio
-            110 => "Ds", // Darmstadtio
-            111 => "Rg", // Roentgenio
-            112 => "Cn", // Copernicio
-            113 => "Nh", // Nihonio
-            114 => "Fl", // Flerovio
-            115 => "Mc", // Moscovio
-            116 => "Lv", // Livermorio
-            117 => "Ts", // Tenesino
-            118 => "Og", // Oganesson
-            _ => "Unknown", // Para elementos más allá de 118
-        }
-    }
-
-    fn get_element_color(proton_count: usize) -> &'static str {
-        // Colores únicos para cada elemento con 50% transparencia
-        match proton_count {
-            1 => "rgba(255, 0, 127, 0.5)",     // Magenta para H
-            2 => "rgba(0, 255, 255, 0.5)",     // Cian para He
-            3 => "rgba(0, 255, 0, 0.5)",       // Verde para Li
-            4 => "rgba(255, 165, 0, 0.5)",     // Naranja para Be
-            5 => "rgba(255, 0, 255, 0.5)",     // Magenta para B
-            6 => "rgba(255, 255, 0, 0.5)",     // Amarillo para C
-            7 => "rgba(255, 20, 147, 0.5)",    // Rosa para N
-            8 => "rgba(0, 191, 255, 0.5)",     // Azul para O
-            9 => "rgba(127, 255, 0, 0.5)",     // Verde lima para F
-            10 => "rgba(255, 105, 180, 0.5)",  // Rosa para Ne
-            11 => "rgba(255, 0, 0, 0.5)",       // Rojo para Na
-            12 => "rgba(192, 192, 192, 0.5)",  // Plata para Mg
-            13 => "rgba(218, 165, 32, 0.5)",   // Dorado para Al
-            14 => "rgba(128, 128, 128, 0.5)",  // Gris para Si
-            15 => "rgba(255, 165, 0, 0.5)",    // Naranja para P
-            16 => "rgba(255, 255, 0, 0.5)",    // Amarillo para S
-            17 => "rgba(0, 255, 0, 0.5)",      // Verde para Cl
-            18 => "rgba(138, 43, 226, 0.5)",   // Púrpura para Ar
-            19 => "rgba(255, 0, 0, 0.5)",      // Rojo para K
-            20 => "rgba(255, 255, 255, 0.5)",  // Blanco para Ca
-            26 => "rgba(184, 134, 11, 0.5)",   // Marrón oscuro para Fe
-            29 => "rgba(184, 115, 51, 0.5)",   // Cobriz para Cu
-            47 => "rgba(192, 192, 192, 0.5)",  // Plata para Ag
-            53 => "rgba(128, 0, 128, 0.5)",     // Púrpura oscuro para I
-            54 => "rgba(0, 255, 255, 0.5)",     // Cian para Xe
-            79 => "rgba(255, 215, 0, 0.5)",     // Oro para Au
-            80 => "rgba(192, 192, 192, 0.5)",  // Plata para Hg
-            82 => "rgba(128, 128, 128, 0.5)",  // Gris para Pb
-            86 => "rgba(220, 20, 60, 0.5)",    // Carmesí para Rn
-            88 => "rgba(0, 255, 0, 0.5)",      // Verde para Ra
-            92 => "rgba(0, 191, 255, 0.5)",     // Azul para U
-            _ => "rgba(255, 255, 255, 0.5)",   // Blanco por defecto
-        }
-    }
-
-    fn draw_element_background(&self, context: &CanvasRenderingContext2d, x: f64, y: f64, proton_count: usize) {
-        let radius = 20.0; // Tamaño similar a protones/neutrones
-        context.begin_path();
-        let _ = context.arc(x, y, radius, 0.0, 2.0 * std::f64::consts::PI);
-        let _ = context.set_fill_style(&Self::get_element_color(proton_count).into());
-        let _ = context.fill();
-        // Agregar contorno para mejor visibilidad
-        let _ = context.set_stroke_style(&"#ffffff".into());
-        let _ = context.set_line_width(1.0);
-        let _ = context.stroke();
-    }
-
-    fn draw_element_symbol(&self, context: &CanvasRenderingContext2d, center_x: f64, center_y: f64, nucleus: &Nucleus) {
-        // Calcular carga real: protones - electrones en este núcleo específico
-        let charge = nucleus.protons.len() as i32 - nucleus.electrons.len() as i32;
-
-        let symbol = Self::get_element_symbol(nucleus.protons.len());
-        let display_text = if charge != 0 {
-            let charge_abs = charge.abs();
-            if charge_abs > 3 {
-                // Limitar la visualización de cargas grandes a +3 o -3 máximo
-                format!("{}{}{}", symbol, if charge > 0 { "+" } else { "-" }, 3)
-            } else {
-                format!("{}{}", symbol, if charge > 0 { "+" } else { "-" }.to_string().repeat(charge_abs as usize))
-            }
-        } else {
-            symbol.to_string()
-        };
-
-        // Texto grande y legible en el centro exacto del núcleo
-        let _ = context.set_fill_style(&"#ffffff".into()); // Texto blanco brillante
-        let _ = context.set_font("bold 20px Arial"); // Tamaño más grande para legibilidad
-        let _ = context.set_text_align("center");
-        let _ = context.set_text_baseline("middle");
-
-        let _ = context.fill_text(&display_text, center_x, center_y);
-    }
-
-    // Dibujar formas características de los subniveles orbitales
-    fn draw_subnivel_shape(&self, context: &CanvasRenderingContext2d, center_x: f64, center_y: f64, radius: f64, color: &str, kind: &str, electron_count: usize) {
-        context.set_stroke_style(&color.into());
-        context.set_line_width(2.0);
-
-        match kind {
-            "s" => {
-                // Subnivel s: forma esférica simétrica
-                let dash_array = js_sys::Array::of2(&4.into(), &2.into());
-                context.set_line_dash(&dash_array);
-                context.begin_path();
-                let _ = context.arc(center_x, center_y, radius, 0.0, 2.0 * std::f64::consts::PI);
-                let _ = context.stroke();
-
-                // Dibujar capa interna para mostrar densidad de probabilidad
-                context.set_line_dash(&js_sys::Array::new()).ok();
-                context.set_line_width(1.0);
-                context.begin_path();
-                let _ = context.arc(center_x, center_y, radius * 0.7, 0.0, 2.0 * std::f64::consts::PI);
-                let _ = context.stroke();
-            },
-
-            "p" => {
-                // Subnivel p: tres lóbulos orientados (px, py, pz)
-                let dash_array = js_sys::Array::of2(&3.into(), &2.into());
-                context.set_line_dash(&dash_array);
-                for (i, angle) in [0.0, std::f64::consts::PI/3.0, 2.0*std::f64::consts::PI/3.0].iter().enumerate() {
-                    if i < electron_count.min(3) {
-                        let lobe_angle = *angle;
-                        let lobe_x = center_x + lobe_angle.cos() * radius * 0.8;
-                        let lobe_y = center_y + lobe_angle.sin() * radius * 0.8;
-
-                        // Dibujar lóbulo en forma de 8
-                        context.begin_path();
-                        context.ellipse(
-                            lobe_x, lobe_y,
-                            radius * 0.3, radius * 0.15,
-                            lobe_angle,
-                            0.0, 2.0 * std::f64::consts::PI
-                        );
-                        let _ = context.stroke();
-
-                        // Lóbulo opuesto (signo negativo)
-                        let opposite_x = center_x - lobe_angle.cos() * radius * 0.8;
-                        let opposite_y = center_y - lobe_angle.sin() * radius * 0.8;
-                        context.begin_path();
-                        context.ellipse(
-                            opposite_x, opposite_y,
-                            radius * 0.3, radius * 0.15,
-                            lobe_angle + std::f64::consts::PI,
-                            0.0, 2.0 * std::f64::consts::PI
-                        );
-                        let _ = context.stroke();
-                    }
-                }
-            },
-
-            "d" => {
-                // Subnivel d: cinco lóbulos en forma de trébol
-                let dash_array = js_sys::Array::of2(&2.into(), &2.into());
-                context.set_line_dash(&dash_array);
-                for i in 0..electron_count.min(5) {
-                    let angle = (i as f64) * 2.0 * std::f64::consts::PI / 5.0;
-                    let lobe_x = center_x + angle.cos() * radius * 0.6;
-                    let lobe_y = center_y + angle.sin() * radius * 0.6;
-
-                    // Lóbulos con forma de pétalo
-                    context.begin_path();
-                    context.ellipse(
-                        lobe_x, lobe_y,
-                        radius * 0.25, radius * 0.12,
-                        angle + std::f64::consts::PI/2.0,
-                        0.0, 2.0 * std::f64::consts::PI
-                    );
-                    let _ = context.stroke();
-
-                    // Lóbulo perpendicular
-                    let perp_x = center_x + angle.cos() * radius * 0.6;
-                    let perp_y = center_y + angle.sin() * radius * 0.6;
-                    context.begin_path();
-                    context.ellipse(
-                        perp_x, perp_y,
-                        radius * 0.15, radius * 0.25,
-                        angle,
-                        0.0, 2.0 * std::f64::consts::PI
-                    );
-                    let _ = context.stroke();
-                }
-            },
-
-            "f" => {
-                // Subnivel f: siete lóbulos con formas complejas
-                let dash_array = js_sys::Array::of2(&1.into(), &1.into());
-                context.set_line_dash(&dash_array);
-                for i in 0..electron_count.min(7) {
-                    let base_angle = (i as f64) * 2.0 * std::f64::consts::PI / 7.0;
-
-                    // Forma compleja con múltiples lóbulos pequeños
-                    for j in 0..3 {
-                        let offset_angle = (j as f64) * std::f64::consts::PI / 6.0;
-                        let lobe_angle = base_angle + offset_angle;
-                        let lobe_radius = radius * (0.4 + j as f64 * 0.1);
-
-                        let lobe_x = center_x + lobe_angle.cos() * lobe_radius;
-                        let lobe_y = center_y + lobe_angle.sin() * lobe_radius;
-
-                        context.begin_path();
-                        context.ellipse(
-                            lobe_x, lobe_y,
-                            radius * 0.12, radius * 0.08,
-                            lobe_angle,
-                            0.0, 2.0 * std::f64::consts::PI
-                        );
-                        let _ = context.stroke();
-                    }
-                }
-            },
-
-            _ => {}
-        }
-
-        // Reset line dash
-        context.set_line_dash(&js_sys::Array::new()).ok();
-    }
-
     // Calcular movimiento orbital realista dentro de las formas de los orbitales
+    #[allow(clippy::too_many_arguments)]
     fn calculate_orbital_motion(&self, nucleus_x: f64, nucleus_y: f64, subnivel: char, principal_n: usize, orbital_idx: usize, pos_in_orbital: usize, time: f64) -> (f64, f64, f64, f64) {
         let base_radius = match principal_n {
             1 => 50.0,  // 1s
@@ -1218,23 +986,148 @@ impl Simulation {
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_survive_removal() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        let ids: Vec<ParticleId> = (0..5).map(|k| simulation.add_particle(100.0 * k as f64, 50.0, ParticleType::Neutron)).collect();
+
+        assert_eq!(simulation.remove_particle(ids[2]).map(|p| p.id), Some(ids[2]));
+        assert!(simulation.remove_particle(ids[2]).is_none());
+        assert_eq!(simulation.index_of(ids[2]), None);
+        for &id in [ids[0], ids[1], ids[3], ids[4]].iter() {
+            let idx = simulation.index_of(id).expect("sigue presente");
+            assert_eq!(simulation.particles[idx].id, id);
+            assert_eq!(simulation.get_particle(id).unwrap().x, 100.0 * ids.iter().position(|&i| i == id).unwrap() as f64);
+        }
+
+        // Los ids no se reutilizan y el vector sigue ordenado para `index_of`
+        let new_id = simulation.add_particle(0.0, 0.0, ParticleType::Proton);
+        assert!(ids.iter().all(|&id| id != new_id));
+        assert_eq!(simulation.index_of(new_id), Some(simulation.particles.len() - 1));
+    }
+
+    #[test]
+    fn loading_json_restores_the_id_order() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        for k in 0..4 {
+            simulation.add_particle(100.0 * k as f64, 50.0, ParticleType::Neutron);
+        }
+        // Archivo editado a mano: partículas desordenadas y un id repetido
+        let mut edited = simulation.clone();
+        edited.particles.reverse();
+        edited.particles.push(edited.particles[0].clone());
+        let loaded = Simulation::from_json(&edited.to_json().unwrap()).unwrap();
+
+        let ids: Vec<ParticleId> = loaded.particles.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+        assert_eq!(loaded.get_particle(2).unwrap().x, 200.0);
+        assert_eq!(loaded.next_id(), 4);
+    }
+
+    #[test]
+    fn damping_does_not_depend_on_the_step_size() {
+        let speed_after = |dt: f64| {
+            let mut simulation = Simulation::new(800.0, 600.0);
+            simulation.params.nuclear_damping = 0.9;
+            simulation.params.dt = dt;
+            let id = simulation.add_particle(400.0, 300.0, ParticleType::Neutron);
+            simulation.particles[0].dx = 1.0;
+            for _ in 0..(10.0 / dt).round() as usize {
+                simulation.update();
+            }
+            simulation.get_particle(id).unwrap().dx
+        };
+
+        // Diez unidades de tiempo frenan lo mismo con cualquier paso: 0.9¹⁰
+        for dt in [1.0, 0.5, 0.25] {
+            assert!((speed_after(dt) - 0.9_f64.powi(10)).abs() < 1e-9, "dt = {}", dt);
+        }
+    }
 
-    
-    fn draw_quarks(&self, context: &CanvasRenderingContext2d, x: f64, y: f64, radius: f64, quark_types: &[&str]) {
-        const PI: f64 = std::f64::consts::PI;
-        let num_quarks = quark_types.len() as f64;
+    #[test]
+    fn removing_a_proton_frees_its_electrons() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_nucleus(400.0, 300.0, 1, 0, 1);
+        simulation.update();
+        let proton = simulation.particles.iter().find(|p| p.ptype == ParticleType::Proton).unwrap().id;
+        let electron = simulation.particles.iter().find(|p| p.ptype == ParticleType::Electron).unwrap().id;
+        assert_eq!(simulation.get_particle(electron).unwrap().orbiting_around, Some(proton));
+
+        simulation.remove_particle(proton);
+        assert_eq!(simulation.get_particle(electron).unwrap().orbiting_around, None);
+        simulation.update();
+        assert_eq!(simulation.index_of(electron), Some(0));
+    }
 
-        for (i, quark_type) in quark_types.iter().enumerate() {
-            let angle = (i as f64 * 2.0 * PI) / num_quarks;
-            let qx = x + angle.cos() * (radius * 0.5);
-            let qy = y + angle.sin() * (radius * 0.5);
+    #[test]
+    fn photons_always_move_at_photon_speed() {
+        use crate::photon::PHOTON_SPEED;
+
+        let mut simulation = Simulation::new(800.0, 600.0);
+        let still = simulation.add_particle(100.0, 100.0, ParticleType::Photon);
+        let aimed = simulation.add_particle_with_velocity(100.0, 200.0, ParticleType::Photon, 0.0, -0.5);
+        for (id, direction) in [(still, (1.0, 0.0)), (aimed, (0.0, -1.0))] {
+            let photon = simulation.get_particle(id).unwrap();
+            assert_eq!(photon.energy, DEFAULT_PHOTON_ENERGY);
+            assert!((photon.dx - direction.0 * PHOTON_SPEED).abs() < 1e-12);
+            assert!((photon.dy - direction.1 * PHOTON_SPEED).abs() < 1e-12);
+        }
 
-            context.begin_path();
-            let _ = context.arc(qx, qy, 5.0, 0.0, 2.0 * PI);
+        simulation.update();
+        assert!((simulation.get_particle(still).unwrap().x - 100.0 - PHOTON_SPEED).abs() < 1e-9);
+    }
+
+    // Protón aislado sin paredes en un campo B uniforme, integrado con RK4
+    fn proton_in_fields(dx: f64, electric_field_y: f64, magnetic_field: f64) -> (Simulation, ParticleId) {
+        let mut simulation = Simulation::new(1000.0, 600.0);
+        simulation.boundary = BoundaryMode::Open;
+        simulation.params.integrator = crate::integrator::Integrator::Rk4;
+        simulation.params.electric_field_y = electric_field_y;
+        simulation.params.magnetic_field = magnetic_field;
+        let id = simulation.add_particle_with_velocity(500.0, 300.0, ParticleType::Proton, dx, 0.0);
+        (simulation, id)
+    }
+
+    #[test]
+    fn charge_in_uniform_b_field_follows_a_cyclotron_orbit() {
+        // r = m·v / (|q|·B) = 3·1 / 0.05 = 60; T = 2π·m / (|q|·B) ≈ 377 pasos
+        let (mut simulation, id) = proton_in_fields(1.0, 0.0, 0.05);
+        let (radius, period) = (60.0, 2.0 * std::f64::consts::PI * 3.0 / 0.05);
+        // Con Bz > 0 una carga positiva gira en sentido horario: el centro queda en −y
+        let center = (500.0, 300.0 - radius);
+        for step in 1..=period.round() as usize {
+            simulation.update();
+            let proton = simulation.get_particle(id).unwrap();
+            assert!(((proton.x - center.0).hypot(proton.y - center.1) - radius).abs() < 0.1);
+            assert!((proton.dx.hypot(proton.dy) - 1.0).abs() < 1e-3);
+            if step == (period / 2.0).round() as usize {
+                assert!((proton.y - (center.1 - radius)).abs() < 0.1);
+            }
+        }
+        let proton = simulation.get_particle(id).unwrap();
+        assert!((proton.x - 500.0).hypot(proton.y - 300.0) < 0.5);
+    }
 
-            let fill_color = if *quark_type == "up" { "#ff6b6b" } else { "#4ecdc4" };
-            let _ = context.set_fill_style(&fill_color.into());
-            let _ = context.fill();
+    #[test]
+    fn crossed_fields_drift_at_e_over_b() {
+        // Partiendo del reposo describe una cicloide: tras cada periodo vuelve al reposo
+        // desplazado v_d·T a lo largo de E×B, con v_d = E/B = 0.4 (hacia +x con E en +y)
+        let (mut simulation, id) = proton_in_fields(0.0, 0.02, 0.05);
+        let period = 2.0 * std::f64::consts::PI * 3.0 / 0.05;
+        let steps = period.round() as usize;
+        for _ in 0..steps {
+            simulation.update();
         }
+        let proton = simulation.get_particle(id).unwrap();
+        let drift = (proton.x - 500.0) / steps as f64;
+        assert!((drift - 0.4).abs() < 0.005);
+        assert!((proton.y - 300.0).abs() < 0.5);
+        assert!(proton.dx.hypot(proton.dy) < 0.01);
     }
 }
@@ -0,0 +1,435 @@
+use crate::particle::{Particle, ParticleType};
+use crate::boundary::BoundaryMode;
+use crate::physics::PhysicsParams;
+use crate::simulation::Simulation;
+use std::fmt;
+
+// Formato binario compacto de `Simulation` (little-endian):
+//
+//   magic "APSS" | versión u16 | canvas_width f64 | canvas_height f64 | time_step f64
+//   | next_id u32 | estado del generador aleatorio u64 (desde v2)
+//   | params (u32 longitud + JSON) | reactor (u32 longitud + JSON, 0 = sin reactor; desde v3)
+//   | enlaces (u32 longitud + JSON, 0 = sin enlaces; desde v5) | contorno u8 (desde v6)
+//   | núcleos retenidos (u32 longitud + JSON, 0 = ninguno; desde v7)
+//   | n u32 | n registros de partícula
+//
+// Los parámetros son pocos y cambian a menudo, así que viajan como JSON (con
+// `serde(default)` cargan aunque falten campos nuevos). Las partículas, que son
+// el grueso del snapshot, van en binario con un layout fijo por versión.
+const MAGIC: &[u8; 4] = b"APSS";
+pub const SNAPSHOT_VERSION: u16 = 7;
+
+const NO_PARENT: u32 = u32::MAX;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    BadMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+    Invalid(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => write!(f, "not a simulation snapshot"),
+            SnapshotError::UnsupportedVersion(v) => write!(f, "unsupported snapshot version {}", v),
+            SnapshotError::Truncated => write!(f, "snapshot is truncated"),
+            SnapshotError::Invalid(msg) => write!(f, "invalid snapshot: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+pub fn encode(simulation: &Simulation) -> Vec<u8> {
+    let params = serde_json::to_vec(&simulation.params).unwrap_or_default();
+    let reactor = match &simulation.reactor {
+        Some(reactor) => serde_json::to_vec(reactor).unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let bonds = match simulation.bonds.is_empty() {
+        true => Vec::new(),
+        false => serde_json::to_vec(&simulation.bonds).unwrap_or_default(),
+    };
+    let held_nuclei = match simulation.held_nuclei.is_empty() {
+        true => Vec::new(),
+        false => serde_json::to_vec(&simulation.held_nuclei).unwrap_or_default(),
+    };
+    let mut out = Vec::with_capacity(
+        52 + params.len() + reactor.len() + bonds.len() + held_nuclei.len() + simulation.particles.len() * 80,
+    );
+
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+    out.extend_from_slice(&simulation.canvas_width.to_le_bytes());
+    out.extend_from_slice(&simulation.canvas_height.to_le_bytes());
+    out.extend_from_slice(&simulation.time_step.to_le_bytes());
+    out.extend_from_slice(&simulation.next_id().to_le_bytes());
+    out.extend_from_slice(&simulation.rng.state().to_le_bytes());
+
+    out.extend_from_slice(&(params.len() as u32).to_le_bytes());
+    out.extend_from_slice(&params);
+    out.extend_from_slice(&(reactor.len() as u32).to_le_bytes());
+    out.extend_from_slice(&reactor);
+    out.extend_from_slice(&(bonds.len() as u32).to_le_bytes());
+    out.extend_from_slice(&bonds);
+    out.push(match simulation.boundary {
+        BoundaryMode::Reflective => 0,
+        BoundaryMode::Periodic => 1,
+        BoundaryMode::Absorbing => 2,
+        BoundaryMode::Open => 3,
+    });
+    out.extend_from_slice(&(held_nuclei.len() as u32).to_le_bytes());
+    out.extend_from_slice(&held_nuclei);
+
+    out.extend_from_slice(&(simulation.particles.len() as u32).to_le_bytes());
+    for particle in &simulation.particles {
+        write_particle(&mut out, particle);
+    }
+    out
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Simulation, SnapshotError> {
+    let mut reader = Reader { bytes, pos: 0 };
+
+    if reader.take(4).map_err(|_| SnapshotError::BadMagic)? != MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+    let version = reader.u16()?;
+    if version == 0 || version > SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+
+    let canvas_width = reader.f64()?;
+    let canvas_height = reader.f64()?;
+    let time_step = reader.f64()?;
+    let next_id = reader.u32()?;
+    let rng_state = if version >= 2 { Some(reader.u64()?) } else { None };
+
+    let params_len = reader.u32()? as usize;
+    let params: PhysicsParams = serde_json::from_slice(reader.take(params_len)?)
+        .map_err(|e| SnapshotError::Invalid(e.to_string()))?;
+    let reactor_len = if version >= 3 { reader.u32()? as usize } else { 0 };
+    let reactor = match reactor_len {
+        0 => None,
+        len => Some(serde_json::from_slice(reader.take(len)?).map_err(|e| SnapshotError::Invalid(e.to_string()))?),
+    };
+    let bonds_len = if version >= 5 { reader.u32()? as usize } else { 0 };
+    let bonds = match bonds_len {
+        0 => Vec::new(),
+        len => serde_json::from_slice(reader.take(len)?).map_err(|e| SnapshotError::Invalid(e.to_string()))?,
+    };
+
+    let boundary = match if version >= 6 { reader.u8()? } else { 0 } {
+        0 => BoundaryMode::Reflective,
+        1 => BoundaryMode::Periodic,
+        2 => BoundaryMode::Absorbing,
+        3 => BoundaryMode::Open,
+        other => return Err(SnapshotError::Invalid(format!("unknown boundary mode {}", other))),
+    };
+    let held_len = if version >= 7 { reader.u32()? as usize } else { 0 };
+    let held_nuclei = match held_len {
+        0 => Vec::new(),
+        len => serde_json::from_slice(reader.take(len)?).map_err(|e| SnapshotError::Invalid(e.to_string()))?,
+    };
+
+    let count = reader.u32()? as usize;
+    let mut particles = Vec::with_capacity(count.min(bytes.len()));
+    for _ in 0..count {
+        particles.push(read_particle(&mut reader, version)?);
+    }
+
+    let mut simulation = Simulation::new(canvas_width, canvas_height);
+    simulation.params = params;
+    simulation.time_step = time_step;
+    simulation.reactor = reactor;
+    simulation.bonds = bonds;
+    simulation.boundary = boundary;
+    simulation.held_nuclei = held_nuclei;
+    simulation.restore_particles(particles, next_id);
+    if let Some(state) = rng_state {
+        simulation.set_seed(state);
+    }
+    Ok(simulation)
+}
+
+fn write_particle(out: &mut Vec<u8>, particle: &Particle) {
+    out.extend_from_slice(&particle.id.to_le_bytes());
+    for value in [particle.x, particle.y, particle.dx, particle.dy, particle.radius, particle.charge, particle.mass] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.push(match particle.ptype {
+        ParticleType::Proton => 0,
+        ParticleType::Neutron => 1,
+        ParticleType::Electron => 2,
+        ParticleType::Positron => 3,
+        ParticleType::Photon => 4,
+    });
+    out.extend_from_slice(&particle.orbiting_around.unwrap_or(NO_PARENT).to_le_bytes());
+    match particle.orbital_position {
+        Some((subnivel, principal_n, orbital_idx, pos_in_orbital)) => {
+            out.push(1);
+            out.push(subnivel as u8);
+            out.push(principal_n as u8);
+            out.push(orbital_idx as u8);
+            out.push(pos_in_orbital as u8);
+        }
+        None => out.extend_from_slice(&[0; 5]),
+    }
+    out.extend_from_slice(&particle.energy.to_le_bytes());
+}
+
+// Lee un registro en el layout de `version` y lo convierte al `Particle` actual
+fn read_particle(reader: &mut Reader, version: u16) -> Result<Particle, SnapshotError> {
+    match version {
+        // v2 y v3 solo añadieron datos a la cabecera (generador, reactor)
+        1..=3 => read_particle_v1(reader),
+        // v4: + energía del fotón (f64) al final del registro (v5 a v7 solo añadieron datos a la cabecera)
+        4..=7 => {
+            let mut particle = read_particle_v1(reader)?;
+            particle.energy = reader.f64()?;
+            Ok(particle)
+        }
+        _ => Err(SnapshotError::UnsupportedVersion(version)),
+    }
+}
+
+fn read_particle_v1(reader: &mut Reader) -> Result<Particle, SnapshotError> {
+    let id = reader.u32()?;
+    let x = reader.f64()?;
+    let y = reader.f64()?;
+    let dx = reader.f64()?;
+    let dy = reader.f64()?;
+    let radius = reader.f64()?;
+    let charge = reader.f64()?;
+    let mass = reader.f64()?;
+    let ptype = match reader.u8()? {
+        0 => ParticleType::Proton,
+        1 => ParticleType::Neutron,
+        2 => ParticleType::Electron,
+        3 => ParticleType::Positron,
+        4 => ParticleType::Photon,
+        other => return Err(SnapshotError::Invalid(format!("unknown particle type {}", other))),
+    };
+    let parent = reader.u32()?;
+    let orbital = reader.take(5)?;
+
+    let mut particle = Particle::new(x, y, ptype, mass);
+    particle.id = id;
+    particle.dx = dx;
+    particle.dy = dy;
+    particle.radius = radius;
+    particle.charge = charge;
+    particle.orbiting_around = if parent == NO_PARENT { None } else { Some(parent) };
+    particle.orbital_position = if orbital[0] == 1 {
+        Some((orbital[1] as char, orbital[2] as usize, orbital[3] as usize, orbital[4] as usize))
+    } else {
+        None
+    };
+    Ok(particle)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        let end = self.pos.checked_add(len).ok_or(SnapshotError::Truncated)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(SnapshotError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, SnapshotError> {
+        let mut buf = [0; 2];
+        buf.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn u64(&mut self) -> Result<u64, SnapshotError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn f64(&mut self) -> Result<f64, SnapshotError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(f64::from_le_bytes(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::{Reactor, RegionKind};
+
+    fn sample_scene() -> Simulation {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_nucleus(300.0, 300.0, 3, 4, 3);
+        simulation.add_particle_with_velocity(50.0, 60.0, ParticleType::Electron, 1.5, -0.25);
+        simulation.add_particle(600.0, 100.0, ParticleType::Neutron);
+        for _ in 0..10 {
+            simulation.update();
+        }
+        simulation
+    }
+
+    #[test]
+    fn round_trip_preserves_state() {
+        let simulation = sample_scene();
+        let restored = decode(&encode(&simulation)).unwrap();
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn round_trip_continues_identically() {
+        let mut simulation = sample_scene();
+        let mut restored = decode(&encode(&simulation)).unwrap();
+        for _ in 0..20 {
+            simulation.update();
+            restored.update();
+        }
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn round_trip_empty_scene() {
+        let simulation = Simulation::new(100.0, 50.0);
+        let restored = decode(&encode(&simulation)).unwrap();
+        assert!(restored.particles.is_empty());
+        assert_eq!(restored.canvas_width, 100.0);
+        assert_eq!(restored.canvas_height, 50.0);
+    }
+
+    #[test]
+    fn loads_version_1_snapshot() {
+        // Un snapshot v1 es uno actual sin el estado del generador, el reactor, los
+        // enlaces, el contorno, los núcleos retenidos ni la energía al final de cada
+        // registro de partícula
+        let simulation = sample_scene();
+        let mut bytes = encode(&simulation);
+        let rng_offset = 4 + 2 + 3 * 8 + 4;
+        let params_len = u32::from_le_bytes(bytes[rng_offset + 8..rng_offset + 12].try_into().unwrap()) as usize;
+        let reactor_offset = rng_offset + 12 + params_len;
+        let held_len = u32::from_le_bytes(bytes[reactor_offset + 9..reactor_offset + 13].try_into().unwrap()) as usize;
+        let (record_v1, record) = (70, 78);
+        for i in (0..simulation.particles.len()).rev() {
+            let energy = reactor_offset + 17 + held_len + i * record + record_v1;
+            bytes.drain(energy..energy + 8);
+        }
+        bytes.drain(reactor_offset..reactor_offset + 13 + held_len);
+        bytes.drain(rng_offset..rng_offset + 8);
+        bytes[4..6].copy_from_slice(&1u16.to_le_bytes());
+
+        let restored = decode(&bytes).unwrap();
+        assert_eq!(restored.particles.len(), simulation.particles.len());
+        assert_eq!(restored.next_id(), simulation.next_id());
+        assert_eq!(restored.params.dt, simulation.params.dt);
+    }
+
+    #[test]
+    fn round_trip_preserves_photons() {
+        let mut simulation = sample_scene();
+        simulation.add_photon(200.0, 200.0, 0.5, 10.2);
+        let restored = decode(&encode(&simulation)).unwrap();
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn round_trip_preserves_reactor() {
+        let mut simulation = sample_scene();
+        let mut reactor = Reactor::default();
+        reactor.add_fuel(400.0, 300.0);
+        reactor.add_region(RegionKind::ControlRod, 500.0, 100.0, 20.0, 300.0);
+        simulation.reactor = Some(reactor);
+
+        let restored = decode(&encode(&simulation)).unwrap();
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn round_trip_preserves_bonds() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.params.bonding_enabled = true;
+        simulation.add_particle(300.0, 300.0, ParticleType::Proton);
+        simulation.add_particle(350.0, 300.0, ParticleType::Electron);
+        simulation.add_particle(420.0, 300.0, ParticleType::Proton);
+        simulation.add_particle(470.0, 300.0, ParticleType::Electron);
+        for _ in 0..5 {
+            simulation.update();
+        }
+        assert!(!simulation.bonds.is_empty());
+
+        let mut restored = decode(&encode(&simulation)).unwrap();
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+        for _ in 0..20 {
+            simulation.update();
+            restored.update();
+        }
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn round_trip_preserves_boundary_mode() {
+        let mut simulation = sample_scene();
+        simulation.boundary = BoundaryMode::Periodic;
+        let restored = decode(&encode(&simulation)).unwrap();
+        assert_eq!(restored.boundary, BoundaryMode::Periodic);
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn round_trip_preserves_held_nuclei() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.params.fission_enabled = true;
+        simulation.add_nucleus(400.0, 300.0, 92, 143, 0);
+        simulation.add_particle_with_velocity(100.0, 300.0, ParticleType::Neutron, 1.0, 0.0);
+        simulation.update();
+        assert_eq!(simulation.held_nuclei.len(), 1);
+
+        let mut restored = decode(&encode(&simulation)).unwrap();
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+        for _ in 0..20 {
+            simulation.update();
+            restored.update();
+        }
+        assert_eq!(restored.to_json().unwrap(), simulation.to_json().unwrap());
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = encode(&sample_scene());
+        bytes[0] = b'X';
+        assert_eq!(decode(&bytes).unwrap_err(), SnapshotError::BadMagic);
+    }
+
+    #[test]
+    fn rejects_future_version() {
+        let mut bytes = encode(&sample_scene());
+        bytes[4..6].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            decode(&bytes).unwrap_err(),
+            SnapshotError::UnsupportedVersion(SNAPSHOT_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_snapshot() {
+        let bytes = encode(&sample_scene());
+        assert_eq!(decode(&bytes[..bytes.len() - 3]).unwrap_err(), SnapshotError::Truncated);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::particle::Particle;
+use std::collections::HashMap;
+
+// Rejilla uniforme (cell list) para consultas de vecinos a corto alcance
+pub struct SpatialGrid {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(particles: &[Particle], cell_size: f64) -> Self {
+        let cell_size = cell_size.max(1.0);
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, particle) in particles.iter().enumerate() {
+            // Coordenadas no finitas no caen en ninguna celda (y no son vecinas de nadie)
+            if !particle.x.is_finite() || !particle.y.is_finite() {
+                continue;
+            }
+            cells
+                .entry(Self::cell_of(particle.x, particle.y, cell_size))
+                .or_default()
+                .push(i);
+        }
+        SpatialGrid { cell_size, cells }
+    }
+
+    fn cell_of(x: f64, y: f64, cell_size: f64) -> (i64, i64) {
+        ((x / cell_size).floor() as i64, (y / cell_size).floor() as i64)
+    }
+
+    // Índices (ordenados) de las partículas en las celdas que cubren el círculo (x, y, radius).
+    // Es un filtro grueso: el llamador sigue comprobando la distancia exacta.
+    pub fn neighbors(&self, x: f64, y: f64, radius: f64) -> Vec<usize> {
+        let mut result = Vec::new();
+        if !x.is_finite() || !y.is_finite() {
+            return result;
+        }
+
+        // `as i64` satura con coordenadas enormes: los límites no deben desbordar
+        let (cx, cy) = Self::cell_of(x, y, self.cell_size);
+        let span = (radius / self.cell_size).ceil().max(1.0) as i64;
+        for gx in cx.saturating_sub(span)..=cx.saturating_add(span) {
+            for gy in cy.saturating_sub(span)..=cy.saturating_add(span) {
+                if let Some(cell) = self.cells.get(&(gx, gy)) {
+                    result.extend_from_slice(cell);
+                }
+            }
+        }
+        result.sort_unstable();
+        result
+    }
+
+    // Igual que `neighbors` en un dominio periódico de `width` × `height`: también
+    // busca alrededor de las imágenes del punto cuando el círculo cruza un borde
+    pub fn neighbors_periodic(&self, x: f64, y: f64, radius: f64, width: f64, height: f64) -> Vec<usize> {
+        let shifts = |value: f64, size: f64| {
+            let mut offsets = vec![0.0];
+            if value - radius < 0.0 {
+                offsets.push(size);
+            }
+            if value + radius > size {
+                offsets.push(-size);
+            }
+            offsets
+        };
+
+        let mut result = Vec::new();
+        for ox in shifts(x, width) {
+            for oy in shifts(y, height) {
+                result.extend(self.neighbors(x + ox, y + oy, radius));
+            }
+        }
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleType;
+    use crate::physics::Physics;
+    use crate::rng::Rng;
+    use crate::simulation::Simulation;
+
+    fn scattered(count: usize, width: f64, height: f64) -> Vec<Particle> {
+        let mut rng = Rng::new(7);
+        (0..count)
+            .map(|_| Particle::new(rng.next_f64() * width, rng.next_f64() * height, ParticleType::Neutron, 1.0))
+            .collect()
+    }
+
+    #[test]
+    fn neighbors_include_every_particle_within_radius() {
+        let particles = scattered(300, 500.0, 400.0);
+        let grid = SpatialGrid::new(&particles, 40.0);
+        for radius in [10.0, 40.0, 95.0] {
+            for (i, p) in particles.iter().enumerate() {
+                let found = grid.neighbors(p.x, p.y, radius);
+                for (j, q) in particles.iter().enumerate() {
+                    if p.distance_to(q) <= radius {
+                        assert!(found.binary_search(&j).is_ok(), "falta {} cerca de {} (r = {})", j, i, radius);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn periodic_neighbors_include_images_across_edges() {
+        let (width, height) = (300.0, 200.0);
+        let particles = scattered(200, width, height);
+        let grid = SpatialGrid::new(&particles, 40.0);
+        for p in &particles {
+            let found = grid.neighbors_periodic(p.x, p.y, 40.0, width, height);
+            for (j, q) in particles.iter().enumerate() {
+                let dx = (p.x - q.x).abs().min(width - (p.x - q.x).abs());
+                let dy = (p.y - q.y).abs().min(height - (p.y - q.y).abs());
+                if dx.hypot(dy) <= 40.0 {
+                    assert!(found.binary_search(&j).is_ok());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn grid_nuclear_forces_match_brute_force() {
+        let mut simulation = Simulation::new(500.0, 400.0);
+        for p in scattered(150, 500.0, 400.0) {
+            simulation.add_particle(p.x, p.y, ParticleType::Neutron);
+        }
+        let particles = &simulation.particles;
+        let forces = simulation.calculate_forces(particles);
+
+        for (i, p) in particles.iter().enumerate() {
+            let (mut fx, mut fy) = (0.0, 0.0);
+            for (j, q) in particles.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let (dx, dy) = (q.x - p.x, q.y - p.y);
+                let distance = dx.hypot(dy).max(1.0);
+                let magnitude = Physics::calculate_nuclear_force(distance, &simulation.params);
+                fx += magnitude * dx / distance;
+                fy += magnitude * dy / distance;
+            }
+            assert!((forces[i].0 - fx).abs() < 1e-9 && (forces[i].1 - fy).abs() < 1e-9, "partícula {}", i);
+        }
+    }
+
+    #[test]
+    fn non_finite_and_huge_coordinates_do_not_panic() {
+        let mut particles = scattered(10, 100.0, 100.0);
+        particles[0].x = f64::NAN;
+        particles[1].y = f64::INFINITY;
+        particles[2].x = 1e300;
+        particles[3].y = -1e300;
+        let grid = SpatialGrid::new(&particles, 40.0);
+
+        assert!(grid.neighbors(f64::NAN, 0.0, 40.0).is_empty());
+        assert!(!grid.neighbors(1e300, particles[2].y, 40.0).is_empty());
+        assert!(!grid.neighbors(particles[3].x, -1e300, 40.0).is_empty());
+        for p in &particles[4..] {
+            assert!(grid.neighbors(p.x, p.y, 200.0).iter().all(|&j| j >= 4));
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::atomic::{angular_momentum, level_energy, photon_wavelength, transition_allowed};
+use crate::particle::ParticleId;
+use crate::simulation::{Nucleus, Simulation};
+use serde::Serialize;
+
+// Niveles que se consideran para el electrón de valencia
+const MAX_PRINCIPAL: usize = 6;
+const SUBSHELL_LETTERS: [char; 4] = ['s', 'p', 'd', 'f'];
+
+// Temperatura de excitación (eV) con la que se pueblan los niveles superiores al
+// estimar las intensidades de emisión
+const EXCITATION_TEMPERATURE: f64 = 1.0;
+
+// Líneas más próximas que esto (nm) se funden en una (niveles degenerados del hidrógeno)
+const LINE_MERGE_WIDTH: f64 = 0.01;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectralLine {
+    pub wavelength: f64, // nm
+    pub energy: f64,     // eV
+    pub intensity: f64,  // Relativa a la línea más intensa (0-1]
+    pub upper: String,   // Subnivel de partida, p. ej. "3p"
+    pub lower: String,
+    pub absorption: bool, // El nivel inferior es el fundamental: también aparece en absorción
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Spectrum {
+    pub protons: usize,
+    pub electrons: usize,
+    pub configuration: String, // Configuración electrónica actual, p. ej. "1s2 2s2 2p6 3s1"
+    pub lines: Vec<SpectralLine>,
+}
+
+impl Simulation {
+    // Espectro de líneas del electrón de valencia de un núcleo detectado. Con un solo
+    // electrón es la serie de Rydberg exacta (13.6·Z²·(1/n₁² − 1/n₂²) eV); con más, los
+    // niveles de `level_energy` (defectos cuánticos aproximados) sobre el núcleo de
+    // electrones internos de la configuración fundamental de `get_orbital_info`. Son las
+    // mismas energías con las que los átomos de la simulación emiten y absorben fotones.
+    pub fn spectrum(&self, nucleus: &Nucleus) -> Spectrum {
+        let protons: Vec<ParticleId> = nucleus.protons.iter().map(|&idx| self.particles[idx].id).collect();
+        let electrons = self.electrons_bound_to(&protons);
+        let z = protons.len();
+        let count = electrons.len();
+
+        // Configuración actual (puede estar excitada) en el orden de llenado
+        let mut occupancy: Vec<((usize, char), usize)> = Vec::new();
+        let mut orbitals: Vec<(char, usize, usize, usize)> =
+            electrons.iter().filter_map(|&id| self.get_particle(id)?.orbital_position).collect();
+        orbitals.sort_by_key(|&orbital| self.orbital_rank(orbital));
+        for (subshell, n, _, _) in orbitals {
+            match occupancy.iter_mut().find(|(level, _)| *level == (n, subshell)) {
+                Some((_, occupied)) => *occupied += 1,
+                None => occupancy.push(((n, subshell), 1)),
+            }
+        }
+        let configuration = occupancy
+            .iter()
+            .map(|((n, subshell), occupied)| format!("{}{}{}", n, subshell, occupied))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Spectrum { protons: z, electrons: count, configuration, lines: self.line_spectrum(z, count) }
+    }
+
+    // Líneas de un átomo con `protons` protones y `electrons` electrones en su estado fundamental
+    pub fn line_spectrum(&self, protons: usize, electrons: usize) -> Vec<SpectralLine> {
+        if electrons == 0 || protons == 0 {
+            return Vec::new();
+        }
+
+        // Núcleo interno: los `electrons - 1` primeros electrones del llenado; el último es el de valencia
+        let mut core: Vec<(usize, char)> = Vec::new();
+        for index in 0..electrons - 1 {
+            if let Some((subshell, n, _, _)) = self.get_orbital_info(index) {
+                core.push((n, subshell));
+            }
+        }
+        let Some((ground_subshell, ground_n, _, _)) = self.get_orbital_info(electrons - 1) else {
+            return Vec::new();
+        };
+        let energy_of = |n: usize, subshell: char| level_energy(protons, electrons, n, subshell);
+        let ground = energy_of(ground_n, ground_subshell);
+
+        // Niveles a los que puede subir el electrón de valencia: con hueco y por encima del fundamental
+        let mut levels = Vec::new();
+        for n in 1..=MAX_PRINCIPAL {
+            for &subshell in SUBSHELL_LETTERS.iter().take(n) {
+                let capacity = 2 * (2 * angular_momentum(subshell) + 1);
+                let occupied = core.iter().filter(|&&level| level == (n, subshell)).count();
+                let energy = energy_of(n, subshell);
+                let is_ground = (n, subshell) == (ground_n, ground_subshell);
+                if occupied < capacity && (energy > ground || is_ground) {
+                    levels.push((n, subshell, energy));
+                }
+            }
+        }
+
+        let mut lines: Vec<SpectralLine> = Vec::new();
+        for &(upper_n, upper, upper_energy) in &levels {
+            for &(lower_n, lower, lower_energy) in &levels {
+                let energy = upper_energy - lower_energy;
+                if energy <= 0.0 || !transition_allowed(upper, lower) {
+                    continue;
+                }
+                // ΔE³ (emisión espontánea) × degeneración del nivel superior / n³ × población térmica
+                let degeneracy = (2 * angular_momentum(upper) + 1) as f64;
+                let population = (-(upper_energy - ground) / EXCITATION_TEMPERATURE).exp();
+                let intensity = energy.powi(3) * degeneracy / (upper_n as f64).powi(3) * population;
+                let wavelength = photon_wavelength(energy);
+                let absorption = (lower_n, lower) == (ground_n, ground_subshell);
+
+                match lines.iter_mut().find(|line| (line.wavelength - wavelength).abs() < LINE_MERGE_WIDTH) {
+                    Some(line) => {
+                        line.intensity += intensity;
+                        line.absorption |= absorption;
+                        // Subniveles degenerados: la línea queda etiquetada solo por n
+                        if line.upper != format!("{}{}", upper_n, upper) {
+                            line.upper = upper_n.to_string();
+                        }
+                        if line.lower != format!("{}{}", lower_n, lower) {
+                            line.lower = lower_n.to_string();
+                        }
+                    }
+                    None => lines.push(SpectralLine {
+                        wavelength,
+                        energy,
+                        intensity,
+                        upper: format!("{}{}", upper_n, upper),
+                        lower: format!("{}{}", lower_n, lower),
+                        absorption,
+                    }),
+                }
+            }
+        }
+
+        let strongest = lines.iter().map(|line| line.intensity).fold(0.0, f64::max);
+        if strongest > 0.0 {
+            for line in &mut lines {
+                line.intensity /= strongest;
+            }
+        }
+        lines.sort_by(|a, b| a.wavelength.total_cmp(&b.wavelength));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(lines: &[SpectralLine], wavelength: f64) -> &SpectralLine {
+        lines
+            .iter()
+            .min_by(|a, b| (a.wavelength - wavelength).abs().total_cmp(&(b.wavelength - wavelength).abs()))
+            .unwrap()
+    }
+
+    #[test]
+    fn hydrogen_lyman_series() {
+        let lines = Simulation::new(100.0, 100.0).line_spectrum(1, 1);
+        let alpha = line(&lines, 121.5);
+        assert!((alpha.energy - 10.2).abs() < 0.01, "{} eV", alpha.energy);
+        assert!((alpha.wavelength - 121.5).abs() < 0.2, "{} nm", alpha.wavelength);
+        assert_eq!((alpha.upper.as_str(), alpha.lower.as_str()), ("2p", "1s"));
+        assert!(alpha.absorption);
+        assert_eq!(alpha.intensity, 1.0);
+
+        let beta = line(&lines, 102.5);
+        assert!((beta.energy - 12.09).abs() < 0.01);
+        assert_eq!(beta.upper, "3p");
+    }
+
+    #[test]
+    fn hydrogen_balmer_series() {
+        let lines = Simulation::new(100.0, 100.0).line_spectrum(1, 1);
+        for (wavelength, upper) in [(656.3, "3"), (486.1, "4"), (434.0, "5"), (410.2, "6")] {
+            let balmer = line(&lines, wavelength);
+            assert!((balmer.wavelength - wavelength).abs() < 0.5, "{} nm", balmer.wavelength);
+            // Los subniveles de n = 2 están degenerados: la línea solo lleva los números principales
+            assert_eq!((balmer.upper.as_str(), balmer.lower.as_str()), (upper, "2"));
+            assert!(!balmer.absorption);
+        }
+        assert!((line(&lines, 656.3).energy - 1.89).abs() < 0.01);
+    }
+
+    #[test]
+    fn hydrogenic_lines_scale_with_z_squared() {
+        let lines = Simulation::new(100.0, 100.0).line_spectrum(2, 1);
+        assert!((line(&lines, 30.4).energy - 4.0 * 10.2).abs() < 0.05);
+    }
+
+    #[test]
+    fn spectrum_reports_the_current_configuration() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_nucleus(400.0, 300.0, 1, 0, 1);
+        simulation.update();
+        let nucleus = simulation.detect_nuclei().remove(0);
+        let spectrum = simulation.spectrum(&nucleus);
+        assert_eq!((spectrum.protons, spectrum.electrons), (1, 1));
+        assert_eq!(spectrum.configuration, "1s1");
+        assert_eq!(spectrum.lines.len(), simulation.line_spectrum(1, 1).len());
+        assert!(simulation.line_spectrum(1, 0).is_empty());
+    }
+}
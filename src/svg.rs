@@ -0,0 +1,284 @@
+use crate::renderer::Renderer;
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+// Backend SVG: acumula los trazos en un documento vectorial independiente
+pub struct SvgRenderer {
+    width: f64,
+    height: f64,
+    body: String,
+    path: String,
+    fill_style: String,
+    stroke_style: String,
+    line_width: f64,
+    line_dash: Vec<f64>,
+    font: String,
+    text_align: String,
+    text_baseline: String,
+}
+
+impl SvgRenderer {
+    pub fn new(width: f64, height: f64) -> Self {
+        SvgRenderer {
+            width,
+            height,
+            body: String::new(),
+            path: String::new(),
+            fill_style: "#000000".to_string(),
+            stroke_style: "#000000".to_string(),
+            line_width: 1.0,
+            line_dash: Vec::new(),
+            font: "10px sans-serif".to_string(),
+            text_align: "start".to_string(),
+            text_baseline: "alphabetic".to_string(),
+        }
+    }
+
+    // Cierra el documento; el fondo negro reproduce el del canvas en la página
+    pub fn finish(self) -> String {
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+                r##"<rect width="100%" height="100%" fill="#000000"/>"##,
+                "{body}</svg>"
+            ),
+            w = self.width,
+            h = self.height,
+            body = self.body
+        )
+    }
+
+    // Añade un arco elíptico al trazo actual (mismo sentido horario que Canvas2D)
+    #[allow(clippy::too_many_arguments)]
+    fn push_arc(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius_x: f64,
+        radius_y: f64,
+        rotation: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) {
+        let point = |angle: f64| {
+            let (ex, ey) = (radius_x * angle.cos(), radius_y * angle.sin());
+            (
+                x + ex * rotation.cos() - ey * rotation.sin(),
+                y + ex * rotation.sin() + ey * rotation.cos(),
+            )
+        };
+
+        // Como Canvas2D: un barrido de 2π o más es la elipse completa; si no, se toma módulo 2π
+        let delta = end_angle - start_angle;
+        let sweep = if delta >= 2.0 * PI { 2.0 * PI } else { delta.rem_euclid(2.0 * PI) };
+        let (sx, sy) = point(start_angle);
+        let command = if self.path.is_empty() { 'M' } else { 'L' };
+        let _ = write!(self.path, "{}{:.2},{:.2}", command, sx, sy);
+
+        // Se parte en tramos de como mucho π, así la bandera large-arc siempre vale 0
+        // (y una elipse completa, que SVG no dibuja con un solo arco, queda en dos mitades)
+        let segments = if sweep > PI { 2 } else { 1 };
+        let segment_sweep = sweep / segments as f64;
+        let rotation_deg = rotation.to_degrees();
+        for k in 1..=segments {
+            let angle = start_angle + segment_sweep * k as f64;
+            let (px, py) = point(angle);
+            let _ = write!(
+                self.path,
+                "A{:.2},{:.2} {:.2} 0 1 {:.2},{:.2}",
+                radius_x, radius_y, rotation_deg, px, py
+            );
+        }
+    }
+
+    fn dash_attribute(&self) -> String {
+        if self.line_dash.is_empty() {
+            String::new()
+        } else {
+            let dash: Vec<String> = self.line_dash.iter().map(|d| d.to_string()).collect();
+            format!(r#" stroke-dasharray="{}""#, dash.join(","))
+        }
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn begin_path(&mut self) {
+        self.path.clear();
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        self.push_arc(x, y, radius, radius, 0.0, start_angle, end_angle);
+    }
+
+    fn ellipse(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius_x: f64,
+        radius_y: f64,
+        rotation: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) {
+        self.push_arc(x, y, radius_x, radius_y, rotation, start_angle, end_angle);
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let _ = write!(self.path, "M{:.2},{:.2}h{:.2}v{:.2}h{:.2}Z", x, y, width, height, -width);
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        let _ = write!(self.path, "M{:.2},{:.2}", x, y);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        let _ = write!(self.path, "L{:.2},{:.2}", x, y);
+    }
+
+    fn stroke(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+        let _ = write!(
+            self.body,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}"{}/>"#,
+            self.path,
+            escape(&self.stroke_style),
+            self.line_width,
+            self.dash_attribute()
+        );
+    }
+
+    fn fill(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+        let _ = write!(
+            self.body,
+            r#"<path d="{}" fill="{}"/>"#,
+            self.path,
+            escape(&self.fill_style)
+        );
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+        let anchor = match self.text_align.as_str() {
+            "center" => "middle",
+            "right" | "end" => "end",
+            _ => "start",
+        };
+        let baseline = match self.text_baseline.as_str() {
+            "middle" => "middle",
+            "top" | "hanging" => "hanging",
+            _ => "alphabetic",
+        };
+        let _ = write!(
+            self.body,
+            r#"<text x="{:.2}" y="{:.2}" fill="{}" style="font: {}" text-anchor="{}" dominant-baseline="{}">{}</text>"#,
+            x,
+            y,
+            escape(&self.fill_style),
+            escape(&self.font),
+            anchor,
+            baseline,
+            escape(text)
+        );
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.fill_style = color.to_string();
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.stroke_style = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    fn set_line_dash(&mut self, segments: &[f64]) {
+        self.line_dash = segments.to_vec();
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.font = font.to_string();
+    }
+
+    fn set_text_align(&mut self, align: &str) {
+        self.text_align = align.to_string();
+    }
+
+    fn set_text_baseline(&mut self, baseline: &str) {
+        self.text_baseline = baseline.to_string();
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::ParticleType;
+    use crate::simulation::Simulation;
+
+    fn stroked_path(draw: impl FnOnce(&mut SvgRenderer)) -> String {
+        let mut renderer = SvgRenderer::new(100.0, 100.0);
+        renderer.begin_path();
+        draw(&mut renderer);
+        renderer.stroke();
+        renderer.finish()
+    }
+
+    #[test]
+    fn quarter_arc_uses_the_short_arc() {
+        let svg = stroked_path(|r| r.arc(50.0, 50.0, 10.0, 0.0, PI / 2.0));
+        assert!(svg.contains(r#"d="M60.00,50.00A10.00,10.00 0.00 0 1 50.00,60.00""#), "{}", svg);
+    }
+
+    #[test]
+    fn three_quarter_arc_is_split_into_short_arcs() {
+        let svg = stroked_path(|r| r.arc(50.0, 50.0, 10.0, 0.0, 1.5 * PI));
+        // Dos tramos de 135°: ninguno necesita large-arc, y juntos llegan a (50, 40)
+        assert_eq!(svg.matches(" 0 1 ").count(), 2, "{}", svg);
+        assert!(svg.contains("A10.00,10.00 0.00 0 1 42.93,57.07A10.00,10.00 0.00 0 1 50.00,40.00"), "{}", svg);
+    }
+
+    #[test]
+    fn full_circle_is_two_half_arcs() {
+        let svg = stroked_path(|r| r.arc(50.0, 50.0, 10.0, 0.0, 2.0 * PI));
+        assert!(svg.contains(r#"d="M60.00,50.00A10.00,10.00 0.00 0 1 40.00,50.00A10.00,10.00 0.00 0 1 60.00,50.00""#), "{}", svg);
+    }
+
+    #[test]
+    fn arc_ending_before_its_start_wraps_around() {
+        // De 3π/2 a π/2: Canvas recorre el barrido módulo 2π, media vuelta pasando por 0
+        let svg = stroked_path(|r| r.arc(50.0, 50.0, 10.0, 1.5 * PI, PI / 2.0));
+        assert!(svg.contains(r#"d="M50.00,40.00A10.00,10.00 0.00 0 1 50.00,60.00""#), "{}", svg);
+    }
+
+    #[test]
+    fn export_svg_draws_every_particle() {
+        let mut simulation = Simulation::new(800.0, 600.0);
+        simulation.add_particle(100.0, 200.0, ParticleType::Neutron);
+        let svg = simulation.export_svg();
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="600""#));
+        assert!(svg.ends_with("</svg>"));
+        // Cuerpo del neutrón (círculo completo de radio 20) más sus tres quarks
+        assert!(svg.contains(r##"d="M120.00,200.00A20.00,20.00 0.00 0 1 80.00,200.00A20.00,20.00 0.00 0 1 120.00,200.00" fill="none" stroke="#ffffff""##), "{}", svg);
+        assert_eq!(svg.matches("<path").count(), 4);
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        let mut renderer = SvgRenderer::new(10.0, 10.0);
+        renderer.fill_text("a<b & \"c\"", 1.0, 2.0);
+        assert!(renderer.finish().contains("a&lt;b &amp; &quot;c&quot;</text>"));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::particle::{Particle, ParticleType};
+use crate::simulation::Simulation;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Cómo se lleva la temperatura de los cuerpos libres (núcleos y partículas sueltas) hacia `PhysicsParams::target_temperature`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Thermostat {
+    // Reescala las velocidades para fijar exactamente la temperatura objetivo en cada paso
+    VelocityRescaling,
+    // Relaja la temperatura hacia el objetivo con tiempo característico `thermostat_time`:
+    //   λ² = 1 + (dt/τ)·(T0/T − 1)
+    Berendsen,
+    // Fricción 1/τ más una fuerza aleatoria gaussiana (Ornstein-Uhlenbeck exacto):
+    //   v ← c·v + √((1 − c²)·T0/m)·ξ,  c = e^(−dt/τ)
+    Langevin,
+}
+
+impl Thermostat {
+    pub fn from_index(index: u32) -> Self {
+        match index {
+            0 => Thermostat::VelocityRescaling,
+            1 => Thermostat::Berendsen,
+            2 => Thermostat::Langevin,
+            _ => Thermostat::Berendsen,
+        }
+    }
+}
+
+// Partículas con masa que no están ligadas a otra (los electrones en órbita siguen
+// una trayectoria impuesta y no intercambian energía con el termostato)
+fn is_free(particle: &Particle) -> bool {
+    particle.ptype != ParticleType::Photon && particle.mass > 0.0 && particle.orbiting_around.is_none()
+}
+
+impl Simulation {
+    // Cuerpos que intercambian energía con el termostato: cada núcleo detectado (todos sus
+    // nucleones, también los que orbitan) cuenta como uno con la velocidad de su centro de
+    // masas, y el resto de partículas libres por separado. Así un gas de átomos tiene la
+    // temperatura de sus átomos y no la de sus A nucleones sueltos.
+    fn thermal_bodies(&self) -> Vec<Vec<usize>> {
+        let mut bodies: Vec<Vec<usize>> = self
+            .detect_nuclei()
+            .into_iter()
+            .map(|nucleus| nucleus.protons.into_iter().chain(nucleus.neutrons).collect())
+            .collect();
+        let in_nucleus: HashSet<usize> = bodies.iter().flatten().copied().collect();
+        bodies.extend(
+            (0..self.particles.len())
+                .filter(|i| !in_nucleus.contains(i) && is_free(&self.particles[*i]))
+                .map(|i| vec![i]),
+        );
+        bodies
+    }
+
+    // Masa y velocidad del centro de masas de un cuerpo
+    fn body_motion(&self, body: &[usize]) -> (f64, f64, f64) {
+        let (mut mass, mut px, mut py) = (0.0, 0.0, 0.0);
+        for &idx in body {
+            let p = &self.particles[idx];
+            mass += p.mass;
+            px += p.mass * p.dx;
+            py += p.mass * p.dy;
+        }
+        (mass, px / mass, py / mass)
+    }
+
+    // Cambia la velocidad del centro de masas de un cuerpo sin tocar su movimiento interno
+    fn shift_body(&mut self, body: &[usize], dvx: f64, dvy: f64) {
+        for &idx in body {
+            self.particles[idx].dx += dvx;
+            self.particles[idx].dy += dvy;
+        }
+    }
+
+    // Temperatura cinética de los cuerpos libres en unidades de la simulación (k_B = 1):
+    // en 2D cada cuerpo tiene dos grados de libertad, así que T = Σ½·M·V² / N
+    pub fn temperature(&self) -> f64 {
+        let bodies = self.thermal_bodies();
+        if bodies.is_empty() {
+            return 0.0;
+        }
+        let kinetic: f64 = bodies
+            .iter()
+            .map(|body| {
+                let (mass, vx, vy) = self.body_motion(body);
+                0.5 * mass * (vx * vx + vy * vy)
+            })
+            .sum();
+        kinetic / bodies.len() as f64
+    }
+
+    pub(crate) fn apply_thermostat(&mut self) {
+        let target = self.params.target_temperature.max(0.0);
+        let tau = self.params.thermostat_time.max(self.params.dt);
+        let bodies = self.thermal_bodies();
+
+        match self.params.thermostat {
+            Thermostat::VelocityRescaling | Thermostat::Berendsen => {
+                // Sin movimiento no hay dirección que reescalar
+                let temperature = self.temperature();
+                if temperature <= 0.0 {
+                    return;
+                }
+
+                let ratio = target / temperature;
+                let scale = if self.params.thermostat == Thermostat::VelocityRescaling {
+                    ratio.sqrt()
+                } else {
+                    (1.0 + self.params.dt / tau * (ratio - 1.0)).max(0.0).sqrt()
+                };
+
+                for body in &bodies {
+                    let (_, vx, vy) = self.body_motion(body);
+                    self.shift_body(body, (scale - 1.0) * vx, (scale - 1.0) * vy);
+                }
+            }
+
+            Thermostat::Langevin => {
+                let c = (-self.params.dt / tau).exp();
+                let noise = (1.0 - c * c).sqrt();
+
+                for body in &bodies {
+                    let (mass, vx, vy) = self.body_motion(body);
+                    let sigma = noise * (target / mass).sqrt();
+                    let new_vx = c * vx + sigma * self.rng.next_gaussian();
+                    let new_vy = c * vy + sigma * self.rng.next_gaussian();
+                    self.shift_body(body, new_vx - vx, new_vy - vy);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Gas ideal de 100 neutrones (sin fuerza nuclear) a T = 1.5 con paredes reflectantes
+    fn gas(thermostat: Thermostat, seed: u64) -> Simulation {
+        let mut simulation = Simulation::new(1000.0, 1000.0);
+        simulation.set_seed(seed);
+        simulation.params.nuclear_attractive = 0.0;
+        simulation.params.nuclear_force = 0.0;
+        simulation.params.thermostat_enabled = true;
+        simulation.params.thermostat = thermostat;
+        simulation.params.target_temperature = 5.0;
+        simulation.params.thermostat_time = 10.0;
+        for k in 0..100 {
+            let angle = k as f64 * 2.4;
+            let (x, y) = (50.0 + 100.0 * (k % 10) as f64, 50.0 + 100.0 * (k / 10) as f64);
+            simulation.add_particle_with_velocity(x, y, ParticleType::Neutron, angle.cos(), angle.sin());
+        }
+        simulation
+    }
+
+    #[test]
+    fn velocity_rescaling_reaches_the_target_in_one_step() {
+        let mut simulation = gas(Thermostat::VelocityRescaling, 1);
+        assert!((simulation.temperature() - 1.5).abs() < 1e-9);
+        simulation.update();
+        assert!((simulation.temperature() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn berendsen_relaxes_towards_the_target() {
+        let mut simulation = gas(Thermostat::Berendsen, 1);
+        let mut previous = simulation.temperature();
+        for _ in 0..100 {
+            simulation.update();
+            let temperature = simulation.temperature();
+            assert!(temperature > previous && temperature < 5.0);
+            previous = temperature;
+        }
+        // Con τ = 10 pasos, tras 100 queda una fracción ~e^(−10) de la diferencia inicial
+        assert!((previous - 5.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn langevin_fluctuates_around_the_target() {
+        let mut simulation = gas(Thermostat::Langevin, 1);
+        for _ in 0..100 {
+            simulation.update();
+        }
+        let steps = 1000;
+        let mut sum = 0.0;
+        for _ in 0..steps {
+            simulation.update();
+            sum += simulation.temperature();
+        }
+        assert!((sum / steps as f64 - 5.0).abs() < 0.25);
+    }
+
+    #[test]
+    fn langevin_is_deterministic_for_a_seed() {
+        let velocities = |seed| {
+            let mut simulation = gas(Thermostat::Langevin, seed);
+            for _ in 0..50 {
+                simulation.update();
+            }
+            simulation.particles.iter().map(|p| (p.dx, p.dy)).collect::<Vec<_>>()
+        };
+        assert_eq!(velocities(7), velocities(7));
+        assert_ne!(velocities(7), velocities(8));
+    }
+
+    // Gas de 12 átomos de He en un dominio periódico, con T = 0.54 por átomo. Con los
+    // enlaces activos (el He no forma ninguno) cada átomo se mueve en bloque; sin fuerza
+    // nuclear solo se repelen y no llegan a juntarse en un cúmulo.
+    fn helium_gas(thermostat: Thermostat, seed: u64) -> Simulation {
+        let mut simulation = Simulation::new(1200.0, 900.0);
+        simulation.boundary = crate::boundary::BoundaryMode::Periodic;
+        simulation.set_seed(seed);
+        simulation.params.bonding_enabled = true;
+        simulation.params.nuclear_attractive = 0.0;
+        simulation.params.nuclear_force = 0.0;
+        simulation.params.thermostat_enabled = true;
+        simulation.params.thermostat = thermostat;
+        simulation.params.target_temperature = 5.0;
+        simulation.params.thermostat_time = 10.0;
+        for k in 0..12 {
+            let first = simulation.particles.len();
+            simulation.add_nucleus(150.0 + 300.0 * (k % 4) as f64, 150.0 + 300.0 * (k / 4) as f64, 2, 2, 2);
+            let angle = k as f64 * 2.4;
+            for particle in simulation.particles[first..].iter_mut().filter(|p| p.ptype != ParticleType::Electron) {
+                particle.dx = 0.3 * angle.cos();
+                particle.dy = 0.3 * angle.sin();
+            }
+        }
+        simulation
+    }
+
+    #[test]
+    fn atoms_count_once_at_their_centre_of_mass() {
+        // ½·12·0.3² por átomo, no por cada uno de sus 4 nucleones
+        let mut simulation = helium_gas(Thermostat::VelocityRescaling, 1);
+        assert!((simulation.temperature() - 0.54).abs() < 1e-9);
+        simulation.update();
+        assert!((simulation.temperature() - 5.0).abs() < 1e-9);
+        assert_eq!(simulation.detect_nuclei().len(), 12);
+    }
+
+    #[test]
+    fn atom_gas_reaches_the_target() {
+        for thermostat in [Thermostat::Berendsen, Thermostat::Langevin] {
+            let mut simulation = helium_gas(thermostat, 1);
+            for _ in 0..100 {
+                simulation.update();
+            }
+            let steps = 1000;
+            let mut sum = 0.0;
+            for _ in 0..steps {
+                simulation.update();
+                sum += simulation.temperature();
+            }
+            let mean = sum / steps as f64;
+            assert!((mean - 5.0).abs() < 0.5, "{:?}: T = {}", thermostat, mean);
+        }
+    }
+}